@@ -0,0 +1,198 @@
+//! The level layout: static tiles plus the objects standing on them.
+
+use crate::render::Frame;
+use crate::theme::Theme;
+use crate::Direction;
+use std::collections::VecDeque;
+
+#[must_use] // ANNOYANCE: not default
+#[repr(u8)] // ANNOYANCE: ugly syntax compared to enum class
+#[derive(PartialEq, Copy, Clone)]
+pub enum Tile {
+    None,
+    Wall,
+    Goal,
+}
+
+#[must_use]
+#[repr(u8)]
+#[derive(PartialEq, Copy, Clone)]
+pub enum Obj {
+    None,
+    Player,
+    Box,
+}
+
+pub type Layer<T> = Vec<T>;
+
+pub type Index = usize;
+pub type Coord = usize;
+pub type Vec2D = (Coord, Coord);
+
+#[must_use]
+#[derive(Clone, PartialEq)]
+pub struct Board {
+    pub width: usize,
+    pub height: usize,
+    pub tiles: Layer<Tile>,
+    pub objects: Layer<Obj>,
+}
+
+impl Board {
+    /// Creates a board where every cell is empty floor.
+    pub fn new(width: usize, height: usize) -> Board {
+        Board {
+            width,
+            height,
+            tiles: vec![Tile::None; width * height],
+            objects: vec![Obj::None; width * height],
+        }
+    }
+
+    #[must_use]
+    pub const fn to_index(&self, (x, y): Vec2D) -> Index {
+        (y * self.width) + x
+    }
+
+    #[must_use]
+    pub const fn to_vec2d(&self, i: Index) -> Vec2D {
+        (i % self.width, i / self.width)
+    }
+
+    /// Returns the cell next to `i` in `direction`, or `None` if that lies
+    /// outside the board.
+    #[must_use]
+    pub fn neighbour(&self, i: Index, direction: Direction) -> Option<Index> {
+        let (x, y) = self.to_vec2d(i);
+        let (ox, oy) = direction.offset();
+        let (tx, ty) = (x as isize + ox, y as isize + oy);
+
+        if tx < 0 || ty < 0 || tx >= self.width as isize || ty >= self.height as isize {
+            return None;
+        }
+
+        Some(self.to_index((tx as usize, ty as usize)))
+    }
+
+    /// Marks the cells reachable from `from` by stepping between cells for
+    /// which `passable` holds. `from` itself is always reached.
+    #[must_use]
+    pub fn flood_fill(&self, from: Index, passable: impl Fn(Index) -> bool) -> Vec<bool> {
+        let mut reached = vec![false; self.tiles.len()];
+        let mut stack = vec![from];
+        reached[from] = true;
+
+        while let Some(i) = stack.pop() {
+            for direction in Direction::iter() {
+                if let Some(n) = self.neighbour(i, direction) {
+                    if !reached[n] && passable(n) {
+                        reached[n] = true;
+                        stack.push(n);
+                    }
+                }
+            }
+        }
+
+        reached
+    }
+
+    /// Finds the shortest walk from `from` to `to` through cells for which
+    /// `passable` holds, or `None` if there is none.
+    #[must_use]
+    pub fn shortest_path(
+        &self,
+        from: Index,
+        to: Index,
+        passable: impl Fn(Index) -> bool,
+    ) -> Option<Vec<Direction>> {
+        let mut previous: Vec<Option<(Index, Direction)>> = vec![None; self.tiles.len()];
+        let mut queue = VecDeque::new();
+        let mut visited = vec![false; self.tiles.len()];
+
+        visited[from] = true;
+        queue.push_back(from);
+
+        while let Some(i) = queue.pop_front() {
+            if i == to {
+                let mut path = Vec::new();
+                let mut current = to;
+                while let Some((prev, d)) = previous[current] {
+                    path.push(d);
+                    current = prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for direction in Direction::iter() {
+                if let Some(n) = self.neighbour(i, direction) {
+                    if !visited[n] && passable(n) {
+                        visited[n] = true;
+                        previous[n] = Some((i, direction));
+                        queue.push_back(n);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Draws the board as rows of cells.
+    pub fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        self.draw_marked(frame, theme, |_| false);
+    }
+
+    /// Draws the board with the cells for which `marked` holds highlighted.
+    pub fn draw_marked(&self, frame: &mut Frame, theme: &Theme, marked: impl Fn(Index) -> bool) {
+        for y in 0..self.height {
+            frame.row(
+                (0..self.width)
+                    .map(|x| {
+                        let i = self.to_index((x, y));
+                        let cell = theme.cell(self.objects[i], self.tiles[i]);
+                        if marked(i) {
+                            cell.highlighted()
+                        } else {
+                            cell
+                        }
+                    })
+                    .collect(),
+            );
+        }
+    }
+
+    /// Draws the board to a string, one line per row.
+    #[must_use]
+    pub fn render(&self, theme: &Theme) -> String {
+        let mut frame = Frame::new();
+        self.draw(&mut frame, theme);
+        frame.to_plain_string()
+    }
+
+    /// The player's cell, or `None` for boards without one, such as those
+    /// from [`Board::new`] or [`crate::xsb::parse_layout`].
+    #[must_use]
+    pub fn find_player(&self) -> Option<Index> {
+        self.objects.iter().position(|x| *x == Obj::Player)
+    }
+
+    #[must_use]
+    pub fn count_goals(&self) -> usize {
+        self.tiles.iter().filter(|x| **x == Tile::Goal).count() // TODO: why all the stars?
+    }
+
+    #[must_use]
+    pub fn count_boxes(&self) -> usize {
+        self.objects.iter().filter(|x| **x == Obj::Box).count()
+    }
+
+    #[must_use]
+    pub fn count_goals_left(&self) -> usize {
+        self.tiles
+            .iter()
+            .zip(self.objects.iter())
+            .filter(|(t, o)| **t == Tile::Goal && **o != Obj::Box)
+            .count()
+    }
+}
@@ -0,0 +1,135 @@
+//! Command line parsing.
+
+use crate::generate::Params;
+use crate::solver::Limits;
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+usage: sokoban [LEVELS] [options]
+       sokoban validate [LEVELS]
+       sokoban generate [options]
+
+  LEVELS             XSB level or level pack (defaults to the built-in level)
+  validate           check the levels for problems and print them as JSON
+  generate           print a new solvable level as XSB
+
+options:
+  --level N          start at level N of the pack
+  --replay LURD      check a solution against the level instead of playing
+  --solve            print a solution for the level instead of playing
+  --export FORMAT    print the level as xsb or rust instead of playing,
+                     after the moves given with --replay if any
+  --max-nodes N      stop solving after expanding N positions
+  --max-time SECS    stop solving after SECS seconds
+  --edit FILE        edit the level in FILE, creating it if needed
+  --size WxH         generate a level of W by H cells (default 9x8)
+  --boxes N          generate a level with N boxes (default 3)
+  --seed SEED        generate the level for SEED, a number or any text such
+                     as a date (defaults to the current time)
+  --theme NAME       draw with the unicode, ascii, emoji or wide theme
+  --color MODE       use none, 256 or truecolor colors";
+
+#[must_use]
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Format {
+    Xsb,
+    Rust,
+}
+
+#[must_use]
+#[derive(Default)]
+pub struct Options {
+    pub path: Option<String>,
+    pub level: usize,
+    pub replay: Option<String>,
+    pub solve: bool,
+    pub validate: bool,
+    pub generate: bool,
+    pub params: Params,
+    pub seed: Option<String>,
+    pub export: Option<Format>,
+    pub edit: Option<String>,
+    pub limits: Limits,
+    pub theme: Option<String>,
+    pub color: Option<String>,
+}
+
+/// Parses the arguments following the program name. On error, returns a
+/// message describing the offending argument.
+pub fn parse_args<I: Iterator<Item = String>>(args: I) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.peekable();
+
+    match args.peek().map(String::as_str) {
+        Some("validate") => options.validate = true,
+        Some("generate") => options.generate = true,
+        _ => {}
+    }
+    if options.validate || options.generate {
+        args.next();
+    }
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("{} requires a value", name))
+        };
+
+        match arg.as_str() {
+            "--level" => {
+                let n = value("--level")?;
+                options.level = match n.parse::<usize>() {
+                    Ok(n) if n >= 1 => n - 1,
+                    _ => return Err(format!("invalid level number {:?}", n)),
+                };
+            }
+            "--replay" => options.replay = Some(value("--replay")?),
+            "--solve" => options.solve = true,
+            "--export" => {
+                let format = value("--export")?;
+                options.export = match format.as_str() {
+                    "xsb" => Some(Format::Xsb),
+                    "rust" => Some(Format::Rust),
+                    _ => return Err(format!("unknown export format {:?}", format)),
+                };
+            }
+            "--edit" => options.edit = Some(value("--edit")?),
+            "--size" => {
+                let size = value("--size")?;
+                let parsed = size
+                    .split_once('x')
+                    .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)));
+                (options.params.width, options.params.height) =
+                    parsed.ok_or_else(|| format!("invalid size {:?}", size))?;
+            }
+            "--boxes" => {
+                let n = value("--boxes")?;
+                options.params.boxes = n
+                    .parse()
+                    .map_err(|_| format!("invalid box count {:?}", n))?;
+            }
+            "--seed" => options.seed = Some(value("--seed")?),
+            "--theme" => options.theme = Some(value("--theme")?),
+            "--color" => options.color = Some(value("--color")?),
+            "--max-nodes" => {
+                let n = value("--max-nodes")?;
+                options.limits.max_nodes = n
+                    .parse()
+                    .map_err(|_| format!("invalid node limit {:?}", n))?;
+            }
+            "--max-time" => {
+                let secs = value("--max-time")?;
+                options.limits.max_time = secs
+                    .parse()
+                    .ok()
+                    .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                    .ok_or_else(|| format!("invalid time limit {:?}", secs))?;
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+            _ if options.path.is_none() => options.path = Some(arg),
+            _ => return Err(format!("unexpected argument {}", arg)),
+        }
+    }
+
+    Ok(options)
+}
@@ -0,0 +1,80 @@
+//! User configuration, read from `$XDG_CONFIG_HOME/sokoban/config`.
+//!
+//! The file holds `key = value` lines; blank lines and lines starting with
+//! `#` are ignored:
+//!
+//! ```text
+//! theme = ascii
+//! color = 256
+//! ```
+
+use std::path::PathBuf;
+
+/// Resolves an XDG base directory, falling back to `default` under the
+/// home directory when the variable is unset.
+#[must_use]
+fn xdg_dir(var: &str, default: &str) -> Option<PathBuf> {
+    match std::env::var_os(var) {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => std::env::var_os("HOME").map(|home| PathBuf::from(home).join(default)),
+    }
+    .map(|dir| dir.join("sokoban"))
+}
+
+#[must_use]
+pub fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// Where saved games and other state are kept.
+#[must_use]
+pub fn data_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_DATA_HOME", ".local/share")
+}
+
+#[must_use]
+#[derive(Default)]
+pub struct Config {
+    pub theme: Option<String>,
+    pub color: Option<String>,
+}
+
+impl Config {
+    /// Reads the configuration file. A missing file yields the defaults;
+    /// on a malformed one, returns a message naming the offending line.
+    pub fn load() -> Result<Config, String> {
+        let path = match config_dir() {
+            Some(dir) => dir.join("config"),
+            None => return Ok(Config::default()),
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(text) => Config::parse(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(_) => Ok(Config::default()),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Config, String> {
+        let mut config = Config::default();
+
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim().to_string()),
+                None => return Err(format!("line {}: expected `key = value`", n + 1)),
+            };
+
+            match key {
+                "theme" => config.theme = Some(value),
+                "color" => config.color = Some(value),
+                _ => return Err(format!("line {}: unknown key {:?}", n + 1, key)),
+            }
+        }
+
+        Ok(config)
+    }
+}
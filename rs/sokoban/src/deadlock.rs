@@ -0,0 +1,119 @@
+//! Detection of positions that can no longer be solved.
+
+use crate::{Board, Direction, Index, Tile};
+
+/// Computes the cells from which a box can never be pushed onto any goal,
+/// regardless of where the other boxes are. Walls are always dead.
+#[must_use]
+pub fn dead_squares(board: &Board) -> Vec<bool> {
+    let is_floor = |i: Index| board.tiles[i] != Tile::Wall;
+
+    let mut dead = vec![true; board.tiles.len()];
+    let mut stack: Vec<Index> = (0..board.tiles.len())
+        .filter(|&i| board.tiles[i] == Tile::Goal)
+        .collect();
+
+    for &goal in &stack {
+        dead[goal] = false;
+    }
+
+    // Pull boxes away from the goals: a box can reach `target` from
+    // `source` if the player has room to stand behind it.
+    while let Some(target) = stack.pop() {
+        for direction in Direction::iter() {
+            let source = match board.neighbour(target, direction.opposite()) {
+                Some(source) if is_floor(source) => source,
+                _ => continue,
+            };
+
+            match board.neighbour(source, direction.opposite()) {
+                Some(pusher) if is_floor(pusher) => {}
+                _ => continue,
+            }
+
+            if dead[source] {
+                dead[source] = false;
+                stack.push(source);
+            }
+        }
+    }
+
+    dead
+}
+
+/// A direction along the axis perpendicular to that of `direction`.
+fn across(direction: Direction) -> Direction {
+    match direction {
+        Direction::Left | Direction::Right => Direction::Down,
+        Direction::Up | Direction::Down => Direction::Right,
+    }
+}
+
+#[must_use]
+struct Freeze<'a> {
+    board: &'a Board,
+    dead: &'a [bool],
+    boxes: &'a [bool],
+    /// Boxes currently under examination, treated as walls to break cycles.
+    visiting: Vec<bool>,
+    off_goal: bool,
+}
+
+impl<'a> Freeze<'a> {
+    #[must_use]
+    fn is_solid(&self, cell: Option<Index>) -> bool {
+        cell.is_none_or(|n| self.board.tiles[n] == Tile::Wall || self.visiting[n])
+    }
+
+    /// Checks whether the box at `i` can never move along the axis of
+    /// `axis` again.
+    #[must_use]
+    fn is_blocked(&mut self, i: Index, axis: Direction) -> bool {
+        let sides = [
+            self.board.neighbour(i, axis),
+            self.board.neighbour(i, axis.opposite()),
+        ];
+
+        if sides.iter().any(|&side| self.is_solid(side)) {
+            return true;
+        }
+
+        if sides.iter().all(|side| side.is_none_or(|n| self.dead[n])) {
+            return true;
+        }
+
+        self.visiting[i] = true;
+        let blocked = sides.iter().flatten().any(|&n| {
+            self.boxes[n] && {
+                // The neighbour can't move along this axis while `i` is
+                // stuck, so it is frozen if it is stuck along the other.
+                let frozen = self.is_blocked(n, across(axis));
+                if frozen && self.board.tiles[n] != Tile::Goal {
+                    self.off_goal = true;
+                }
+                frozen
+            }
+        });
+        self.visiting[i] = false;
+
+        blocked
+    }
+}
+
+/// Checks whether the box at `i` is frozen in place together with at least
+/// one box that is not on a goal, which includes 2x2 blocks of boxes and
+/// walls. `boxes` marks the cells holding a box.
+#[must_use]
+pub fn is_freeze_deadlock(board: &Board, dead: &[bool], boxes: &[bool], i: Index) -> bool {
+    let mut freeze = Freeze {
+        board,
+        dead,
+        boxes,
+        visiting: vec![false; board.tiles.len()],
+        off_goal: board.tiles[i] != Tile::Goal,
+    };
+
+    freeze.is_blocked(i, Direction::Right)
+        && freeze.is_blocked(i, Direction::Down)
+        && freeze.off_goal
+}
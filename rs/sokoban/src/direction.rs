@@ -0,0 +1,75 @@
+//! The four directions the player can move in.
+
+#[must_use]
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    pub fn iter() -> impl Iterator<Item = Direction> {
+        Direction::ALL.iter().copied()
+    }
+
+    /// The change in `(x, y)` when moving one cell this way.
+    #[must_use]
+    pub const fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        }
+    }
+
+    pub const fn opposite(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    /// Converts a move into its LURD notation: lowercase for plain moves,
+    /// uppercase for pushes.
+    #[must_use]
+    pub const fn to_lurd(self, pushed: bool) -> char {
+        let c = match self {
+            Direction::Up => 'u',
+            Direction::Down => 'd',
+            Direction::Left => 'l',
+            Direction::Right => 'r',
+        };
+
+        if pushed {
+            c.to_ascii_uppercase()
+        } else {
+            c
+        }
+    }
+
+    /// Parses a LURD character into a direction and whether it is a push.
+    #[must_use]
+    pub fn from_lurd(c: char) -> Option<(Direction, bool)> {
+        let direction = match c.to_ascii_lowercase() {
+            'u' => Direction::Up,
+            'd' => Direction::Down,
+            'l' => Direction::Left,
+            'r' => Direction::Right,
+            _ => return None,
+        };
+
+        Some((direction, c.is_ascii_uppercase()))
+    }
+}
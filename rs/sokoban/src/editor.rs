@@ -0,0 +1,101 @@
+//! Editing levels cell by cell.
+//!
+//! Cells are set by typing their XSB glyph at the cursor, so `#` places a
+//! wall, `$` a box, `+` the player on a goal and so on.
+
+use crate::render::Frame;
+use crate::theme::Theme;
+use crate::{xsb, Board, Direction, Obj, Tile, Vec2D};
+
+#[must_use]
+pub struct Editor {
+    board: Board,
+    cursor: Vec2D,
+}
+
+impl Editor {
+    pub fn new(board: Board) -> Editor {
+        Editor {
+            board,
+            cursor: (0, 0),
+        }
+    }
+
+    /// A `width` by `height` room with walls all around, at least one cell
+    /// in each direction.
+    pub fn empty(width: usize, height: usize) -> Editor {
+        let (width, height) = (width.max(1), height.max(1));
+        let mut board = Board::new(width, height);
+        for i in 0..board.tiles.len() {
+            let (x, y) = board.to_vec2d(i);
+            if x == 0 || y == 0 || x + 1 == width || y + 1 == height {
+                board.tiles[i] = Tile::Wall;
+            }
+        }
+
+        Editor::new(board)
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    #[must_use]
+    pub fn cursor(&self) -> Vec2D {
+        self.cursor
+    }
+
+    pub fn move_cursor(&mut self, direction: Direction) {
+        let i = self.board.to_index(self.cursor);
+        if let Some(n) = self.board.neighbour(i, direction) {
+            self.cursor = self.board.to_vec2d(n);
+        }
+    }
+
+    /// Sets the cell under the cursor to what the XSB `glyph` stands for,
+    /// returning `false` if it isn't one. Placing the player removes it
+    /// from wherever it stood before.
+    #[must_use]
+    pub fn place(&mut self, glyph: char) -> bool {
+        let (tile, obj) = match xsb::glyph_to_cell(glyph) {
+            Some(cell) => cell,
+            None => return false,
+        };
+
+        if obj == Obj::Player {
+            for o in self.board.objects.iter_mut().filter(|o| **o == Obj::Player) {
+                *o = Obj::None;
+            }
+        }
+
+        let i = self.board.to_index(self.cursor);
+        self.board.tiles[i] = tile;
+        self.board.objects[i] = obj;
+        true
+    }
+
+    /// Changes the board size, keeping the top-left corner in place. New
+    /// cells are empty floor.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        let (width, height) = (width.max(1), height.max(1));
+        let mut board = Board::new(width, height);
+
+        for y in 0..height.min(self.board.height) {
+            for x in 0..width.min(self.board.width) {
+                let (from, to) = (self.board.to_index((x, y)), board.to_index((x, y)));
+                board.tiles[to] = self.board.tiles[from];
+                board.objects[to] = self.board.objects[from];
+            }
+        }
+
+        let (cx, cy) = self.cursor;
+        self.cursor = (cx.min(width - 1), cy.min(height - 1));
+        self.board = board;
+    }
+
+    /// Draws the board with the cell under the cursor highlighted.
+    pub fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        let cursor = self.board.to_index(self.cursor);
+        self.board.draw_marked(frame, theme, |i| i == cursor);
+    }
+}
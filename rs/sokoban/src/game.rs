@@ -0,0 +1,428 @@
+//! Game state: the board being played plus move history and statistics.
+
+use crate::deadlock;
+use crate::render::Frame;
+use crate::solver::{self, Limits, SolveError};
+use crate::theme::Theme;
+use crate::{Board, Coord, Direction, Index, Obj, Tile};
+use std::time::{Duration, Instant};
+
+#[must_use]
+pub fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+#[must_use]
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum GameStatus {
+    Playing,
+    Won,
+}
+
+/// A single successful player move, as recorded in the undo history.
+#[must_use]
+#[derive(Copy, Clone)]
+struct Step {
+    direction: Direction,
+    pushed: bool,
+    goals_delta: isize,
+}
+
+/// The solver's advice for the current position.
+#[must_use]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hint {
+    /// The next move of a solution.
+    pub direction: Direction,
+    /// The box the solution pushes next, and the direction it goes in.
+    pub push: (Index, Direction),
+}
+
+#[must_use]
+pub struct Game {
+    board: Board,
+    player_index: Coord,
+    goals_left: usize,
+    dead_squares: Vec<bool>,
+    deadlocked: bool,
+    moves: usize,
+    pushes: usize,
+    history: Vec<Step>,
+    redo_stack: Vec<Step>,
+    hint: Option<Hint>,
+    hints: usize,
+    started: Instant,
+    finished: Option<Duration>,
+}
+
+impl Game {
+    /// Starts a game of `board`.
+    ///
+    /// # Panics
+    ///
+    /// If `board` has no player. Boards from [`crate::xsb::parse_board`]
+    /// always have one.
+    pub fn new(board: Board) -> Game {
+        let mut game = Game {
+            player_index: board.find_player().expect("board has no player"),
+            goals_left: board.count_goals_left(),
+            dead_squares: deadlock::dead_squares(&board),
+            deadlocked: false,
+            board,
+            moves: 0,
+            pushes: 0,
+            history: Vec::new(),
+            redo_stack: Vec::new(),
+            hint: None,
+            hints: 0,
+            started: Instant::now(),
+            finished: None,
+        };
+
+        game.deadlocked = game.find_deadlock();
+        game
+    }
+
+    #[must_use]
+    fn box_layer(&self) -> Vec<bool> {
+        self.board.objects.iter().map(|o| *o == Obj::Box).collect()
+    }
+
+    #[must_use]
+    fn is_box_deadlocked(&self, boxes: &[bool], i: Index) -> bool {
+        (self.dead_squares[i] && self.board.tiles[i] != Tile::Goal)
+            || deadlock::is_freeze_deadlock(&self.board, &self.dead_squares, boxes, i)
+    }
+
+    /// Checks every box for deadlocks, for positions not reached by a push.
+    #[must_use]
+    fn find_deadlock(&self) -> bool {
+        let boxes = self.box_layer();
+        (0..boxes.len()).any(|i| boxes[i] && self.is_box_deadlocked(&boxes, i))
+    }
+
+    pub fn status(&self) -> GameStatus {
+        if self.goals_left == 0 {
+            GameStatus::Won
+        } else {
+            GameStatus::Playing
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    #[must_use]
+    pub fn player_index(&self) -> Index {
+        self.player_index
+    }
+
+    #[must_use]
+    pub fn goals_left(&self) -> usize {
+        self.goals_left
+    }
+
+    #[must_use]
+    pub fn moves(&self) -> usize {
+        self.moves
+    }
+
+    #[must_use]
+    pub fn pushes(&self) -> usize {
+        self.pushes
+    }
+
+    /// How many hints were asked for.
+    #[must_use]
+    pub fn hints(&self) -> usize {
+        self.hints
+    }
+
+    /// Sets the number of hints used, for games resumed from a save.
+    pub fn set_hints(&mut self, hints: usize) {
+        self.hints = hints;
+    }
+
+    /// The hint shown for the current position, if any.
+    pub fn current_hint(&self) -> Option<Hint> {
+        self.hint
+    }
+
+    /// Solves the level from the current position and shows the first
+    /// move of the solution until the player moves. Returns `None` if the
+    /// level is already won.
+    pub fn hint(&mut self, limits: &Limits) -> Result<Option<Hint>, SolveError> {
+        let solution = solver::solve(&self.board, limits)?;
+        let mut moves = solution.lurd.chars().filter_map(Direction::from_lurd);
+
+        let direction = match moves.clone().next() {
+            Some((direction, _)) => direction,
+            None => return Ok(None),
+        };
+
+        // The solution's moves were made on the board, so every cell exists.
+        let mut player = self.player_index;
+        let push = loop {
+            let (direction, pushed) = moves.next().unwrap();
+            let next = self.board.neighbour(player, direction).unwrap();
+            if pushed {
+                break (next, direction);
+            }
+            player = next;
+        };
+
+        self.hint = Some(Hint { direction, push });
+        self.hints += 1;
+        Ok(self.hint)
+    }
+
+    /// The cells the player can walk to without pushing a box.
+    #[must_use]
+    pub fn reachable(&self) -> Vec<bool> {
+        let board = &self.board;
+        board.flood_fill(self.player_index, |n| {
+            board.tiles[n] != Tile::Wall && board.objects[n] != Obj::Box
+        })
+    }
+
+    /// Walks the player to `target` along a shortest path, recording each
+    /// step as a move. Returns `false` without moving if `target` can't be
+    /// reached without pushing a box.
+    pub fn go_to(&mut self, target: Index) -> bool {
+        if self.status() == GameStatus::Won {
+            return false;
+        }
+
+        let board = &self.board;
+        let path = board.shortest_path(self.player_index, target, |n| {
+            board.tiles[n] != Tile::Wall && board.objects[n] != Obj::Box
+        });
+
+        match path {
+            Some(path) if !path.is_empty() => {
+                for direction in path {
+                    let moved = self.move_player(direction);
+                    debug_assert!(moved);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the position can no longer be solved.
+    #[must_use]
+    pub fn is_deadlocked(&self) -> bool {
+        self.deadlocked
+    }
+
+    /// The moves made so far, in LURD notation.
+    #[must_use]
+    pub fn lurd(&self) -> String {
+        self.history
+            .iter()
+            .map(|step| step.direction.to_lurd(step.pushed))
+            .collect()
+    }
+
+    /// Time spent on the level, frozen once it is won.
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.finished.unwrap_or_else(|| self.started.elapsed())
+    }
+
+    /// Restarts the clock as if `elapsed` had already been spent on the
+    /// level, for games resumed from a save.
+    pub fn set_elapsed(&mut self, elapsed: Duration) {
+        let now = Instant::now();
+        self.started = now.checked_sub(elapsed).unwrap_or(now);
+        if self.finished.is_some() {
+            self.finished = Some(elapsed);
+        }
+    }
+
+    fn obj_at(&mut self, i: Index) -> &mut Obj {
+        &mut self.board.objects[i]
+    }
+
+    fn tile_at(&mut self, i: Index) -> &mut Tile {
+        &mut self.board.tiles[i]
+    }
+
+    /// Pushes the box at `source` one cell in `direction`, treating cells
+    /// off the board as walls.
+    #[must_use]
+    fn move_box(&mut self, source: Index, direction: Direction) -> bool {
+        let target = match self.board.neighbour(source, direction) {
+            Some(target) => target,
+            None => return false,
+        };
+
+        if *self.tile_at(target) == Tile::Wall || *self.obj_at(target) != Obj::None {
+            return false;
+        }
+
+        if *self.tile_at(source) == Tile::Goal {
+            self.goals_left += 1;
+        }
+
+        if *self.tile_at(target) == Tile::Goal {
+            self.goals_left -= 1;
+        }
+
+        self.board.objects.swap(target, source);
+
+        // Deadlocks are permanent, so only the pushed box can introduce one.
+        if !self.deadlocked {
+            let boxes = self.box_layer();
+            self.deadlocked = self.is_box_deadlocked(&boxes, target);
+        }
+
+        true
+    }
+
+    pub fn move_player(&mut self, direction: Direction) -> bool {
+        if !self.step(direction) {
+            return false;
+        }
+
+        self.redo_stack.clear();
+        true
+    }
+
+    /// Takes back the last move, returning `false` if there is none.
+    pub fn undo(&mut self) -> bool {
+        let step = match self.history.pop() {
+            Some(step) => step,
+            None => return false,
+        };
+
+        // Undone moves were made on the board, so both cells exist.
+        let previous = self
+            .board
+            .neighbour(self.player_index, step.direction.opposite())
+            .unwrap();
+
+        self.board.objects.swap(previous, self.player_index);
+
+        if step.pushed {
+            let pushed_box = self
+                .board
+                .neighbour(self.player_index, step.direction)
+                .unwrap();
+            self.board.objects.swap(pushed_box, self.player_index);
+            self.pushes -= 1;
+        }
+
+        self.player_index = previous;
+        self.goals_left = (self.goals_left as isize - step.goals_delta) as usize;
+        self.moves -= 1;
+        self.finished = None;
+        self.deadlocked = self.find_deadlock();
+        self.hint = None;
+
+        self.redo_stack.push(step);
+        true
+    }
+
+    /// Replays the last undone move, returning `false` if there is none.
+    pub fn redo(&mut self) -> bool {
+        match self.redo_stack.pop() {
+            Some(step) => self.step(step.direction),
+            None => false,
+        }
+    }
+
+    #[must_use]
+    fn step(&mut self, direction: Direction) -> bool {
+        if self.status() == GameStatus::Won {
+            return false;
+        }
+
+        let goals_before = self.goals_left;
+
+        let target = match self.board.neighbour(self.player_index, direction) {
+            Some(target) => target,
+            None => return false,
+        };
+
+        let pushing = *self.obj_at(target) == Obj::Box;
+        let couldnt_push_box = pushing && !self.move_box(target, direction);
+
+        if *self.tile_at(target) == Tile::Wall || couldnt_push_box {
+            return false;
+        }
+
+        self.board.objects.swap(target, self.player_index);
+        self.player_index = target;
+        self.moves += 1;
+        if pushing {
+            self.pushes += 1;
+        }
+
+        self.hint = None;
+        self.history.push(Step {
+            direction,
+            pushed: pushing,
+            goals_delta: self.goals_left as isize - goals_before as isize,
+        });
+
+        if self.status() == GameStatus::Won {
+            self.finished = Some(self.started.elapsed());
+        }
+
+        true
+    }
+
+    pub fn draw_summary(&self, frame: &mut Frame) {
+        frame.text("Level complete!");
+        frame.blank();
+        frame.text(&format!("Moves:  {}", self.moves));
+        frame.text(&format!("Pushes: {}", self.pushes));
+        frame.text(&format!("Time:   {}", format_duration(self.elapsed())));
+        frame.text(&format!("Hints:  {}", self.hints));
+        frame.blank();
+        frame.text(&format!("Solution: {}", self.lurd()));
+    }
+
+    /// Draws the board, with the cells of the current hint highlighted, and
+    /// the status lines.
+    pub fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        let hinted = self.hint.map_or_else(Vec::new, |hint| {
+            let target = self.board.neighbour(self.player_index, hint.direction);
+            target.into_iter().chain(Some(hint.push.0)).collect()
+        });
+
+        self.draw_marked(frame, theme, |i| hinted.contains(&i));
+    }
+
+    /// Draws the board with the cells for which `marked` holds highlighted,
+    /// and the status lines.
+    pub fn draw_marked(&self, frame: &mut Frame, theme: &Theme, marked: impl Fn(Index) -> bool) {
+        self.board.draw_marked(frame, theme, marked);
+        frame.blank();
+        frame.text(&format!("Goals left: {}", self.goals_left));
+        if self.deadlocked {
+            frame.text("Deadlock! This position can't be solved; undo or restart.");
+        }
+        if self.hints > 0 {
+            frame.text(&format!(
+                "Moves: {}, pushes: {}, hints: {}",
+                self.moves, self.pushes, self.hints
+            ));
+        } else {
+            frame.text(&format!("Moves: {}, pushes: {}", self.moves, self.pushes));
+        }
+        frame.text(&format!("LURD: {}", self.lurd()));
+    }
+
+    /// Draws the board and status lines to a string.
+    #[must_use]
+    pub fn render(&self, theme: &Theme) -> String {
+        let mut frame = Frame::new();
+        self.draw(&mut frame, theme);
+        frame.to_plain_string()
+    }
+}
@@ -0,0 +1,272 @@
+//! Generation of new levels.
+//!
+//! A level starts as a walled room with some random inner walls, with every
+//! box on a goal. Boxes are then pulled away from the goals at random, the
+//! reverse of pushing them, so the result is always solvable. Of a handful
+//! of candidates, the one the solver had to work hardest on is kept and
+//! rated by how many positions it searched per push of its solution.
+
+use crate::records::fnv1a;
+use crate::solver::{self, Limits, Solution};
+use crate::validate;
+use crate::{Board, Direction, Index, Obj, Tile};
+use std::fmt;
+use std::time::Duration;
+
+/// Share of the room's inside turned into walls, in percent.
+const INNER_WALLS: usize = 20;
+const LAYOUT_ATTEMPTS: usize = 100;
+const CANDIDATES: usize = 8;
+
+/// Deterministic SplitMix64 generator, so a seed always yields the same
+/// level.
+#[must_use]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    /// Seeds from a number, or else from the hash of any other text such
+    /// as a date.
+    pub fn from_text(seed: &str) -> Rng {
+        Rng::new(seed.parse().unwrap_or_else(|_| fnv1a(seed.as_bytes())))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`, where `n` must not be zero.
+    #[must_use]
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+#[must_use]
+#[derive(Copy, Clone)]
+pub struct Params {
+    pub width: usize,
+    pub height: usize,
+    pub boxes: usize,
+}
+
+impl Default for Params {
+    fn default() -> Params {
+        Params {
+            width: 9,
+            height: 8,
+            boxes: 3,
+        }
+    }
+}
+
+#[must_use]
+#[derive(Debug, PartialEq)]
+pub enum GenerateError {
+    TooSmall,
+    NoBoxes,
+    NoLevel,
+}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GenerateError::TooSmall => write!(f, "level is too small for that many boxes"),
+            GenerateError::NoBoxes => write!(f, "level needs at least one box"),
+            GenerateError::NoLevel => write!(f, "no solvable level found for this seed"),
+        }
+    }
+}
+
+impl std::error::Error for GenerateError {}
+
+#[must_use]
+pub struct Generated {
+    pub board: Board,
+    /// The solver's solution, whose statistics rate the level.
+    pub solution: Solution,
+}
+
+impl Generated {
+    #[must_use]
+    pub fn difficulty(&self) -> &'static str {
+        match self.solution.nodes / self.solution.pushes.max(1) {
+            0..=1 => "easy",
+            2..=19 => "medium",
+            _ => "hard",
+        }
+    }
+}
+
+/// Walls in the whole board apart from a room of floor with random inner
+/// walls, keeping only the largest connected part of the room.
+fn layout(params: &Params, rng: &mut Rng) -> Board {
+    let (width, height) = (params.width, params.height);
+    let mut board = Board::new(width, height);
+
+    for i in 0..board.tiles.len() {
+        let (x, y) = board.to_vec2d(i);
+        let border = x == 0 || y == 0 || x + 1 == width || y + 1 == height;
+        if border || rng.below(100) < INNER_WALLS {
+            board.tiles[i] = Tile::Wall;
+        }
+    }
+
+    let mut largest = vec![false; board.tiles.len()];
+    let mut seen = vec![false; board.tiles.len()];
+    for i in 0..board.tiles.len() {
+        if seen[i] || board.tiles[i] == Tile::Wall {
+            continue;
+        }
+
+        let region = board.flood_fill(i, |n| board.tiles[n] != Tile::Wall);
+        let size = region.iter().filter(|&&r| r).count();
+        for (j, &r) in region.iter().enumerate() {
+            seen[j] |= r;
+        }
+        if size > largest.iter().filter(|&&r| r).count() {
+            largest = region;
+        }
+    }
+
+    for (tile, &keep) in board.tiles.iter_mut().zip(&largest) {
+        if !keep {
+            *tile = Tile::Wall;
+        }
+    }
+
+    board
+}
+
+/// Pulls boxes away from their goals at random, moving the player along.
+fn scramble(board: &mut Board, player: &mut Index, rng: &mut Rng) {
+    let is_free =
+        |board: &Board, i: Index| board.tiles[i] != Tile::Wall && board.objects[i] != Obj::Box;
+    let pulls = board.tiles.len() * 2;
+
+    for _ in 0..pulls {
+        let reached = board.flood_fill(*player, |n| is_free(board, n));
+
+        // A box at `b` is pulled in `direction` by the player stepping from
+        // `stand` to `to`.
+        let mut candidates = Vec::new();
+        for b in (0..board.objects.len()).filter(|&b| board.objects[b] == Obj::Box) {
+            for direction in Direction::iter() {
+                let stand = match board.neighbour(b, direction) {
+                    Some(stand) if reached[stand] => stand,
+                    _ => continue,
+                };
+                match board.neighbour(stand, direction) {
+                    Some(to) if is_free(board, to) => candidates.push((b, stand, to)),
+                    _ => {}
+                }
+            }
+        }
+
+        if candidates.is_empty() {
+            break;
+        }
+
+        let (b, stand, to) = candidates[rng.below(candidates.len())];
+        board.objects[b] = Obj::None;
+        board.objects[stand] = Obj::Box;
+        *player = to;
+    }
+
+    // Leave the player anywhere it could have walked to.
+    let reached = board.flood_fill(*player, |n| is_free(board, n));
+    let spots: Vec<Index> = (0..reached.len()).filter(|&i| reached[i]).collect();
+    *player = spots[rng.below(spots.len())];
+    board.objects[*player] = Obj::Player;
+}
+
+/// Builds one candidate level, or `None` if the layout left too little
+/// room.
+#[must_use]
+fn candidate(params: &Params, rng: &mut Rng) -> Option<Board> {
+    let mut board = layout(params, rng);
+
+    let mut floor: Vec<Index> = (0..board.tiles.len())
+        .filter(|&i| board.tiles[i] != Tile::Wall)
+        .collect();
+    if floor.len() < params.boxes * 2 + 2 {
+        return None;
+    }
+
+    for i in 0..=params.boxes {
+        let j = i + rng.below(floor.len() - i);
+        floor.swap(i, j);
+    }
+
+    for &goal in &floor[..params.boxes] {
+        board.tiles[goal] = Tile::Goal;
+        board.objects[goal] = Obj::Box;
+    }
+
+    let mut player = floor[params.boxes];
+    scramble(&mut board, &mut player, rng);
+
+    let solved = board.count_goals_left() == 0;
+    if solved || !validate::is_valid(&validate::validate(&board)) {
+        return None;
+    }
+
+    Some(board)
+}
+
+/// Generates a level from `rng`, keeping the candidate whose solution took
+/// the longest search, or failing a tie, the most pushes.
+pub fn generate(params: &Params, rng: &mut Rng) -> Result<Generated, GenerateError> {
+    if params.boxes == 0 {
+        return Err(GenerateError::NoBoxes);
+    }
+    if params.width < 3 || params.height < 3 {
+        return Err(GenerateError::TooSmall);
+    }
+    let inside = (params.width - 2) * (params.height - 2);
+    if inside < params.boxes * 2 + 2 {
+        return Err(GenerateError::TooSmall);
+    }
+
+    // Only nodes are limited, so that a seed gives the same level however
+    // fast the machine is.
+    let limits = Limits {
+        max_nodes: 200_000,
+        max_time: Duration::MAX,
+    };
+
+    let mut best: Option<Generated> = None;
+    let mut found = 0;
+
+    for _ in 0..LAYOUT_ATTEMPTS {
+        let board = match candidate(params, rng) {
+            Some(board) => board,
+            None => continue,
+        };
+
+        if let Ok(solution) = solver::solve(&board, &limits) {
+            let effort = |s: &Solution| (s.nodes, s.pushes);
+            if best
+                .as_ref()
+                .is_none_or(|b| effort(&solution) > effort(&b.solution))
+            {
+                best = Some(Generated { board, solution });
+            }
+            found += 1;
+            if found == CANDIDATES {
+                break;
+            }
+        }
+    }
+
+    best.ok_or(GenerateError::NoLevel)
+}
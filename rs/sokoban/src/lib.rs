@@ -0,0 +1,38 @@
+//! A terminal Sokoban engine.
+//!
+//! Levels are parsed from XSB text with [`xsb::parse_board`] or
+//! [`pack::LevelPack::parse`] into a [`Board`], which a [`Game`] then plays:
+//!
+//! ```
+//! use sokoban::{xsb, Direction, Game, GameStatus};
+//!
+//! let board = xsb::parse_board("#####\n#@$.#\n#####").unwrap();
+//! let mut game = Game::new(board);
+//!
+//! assert!(game.move_player(Direction::Right));
+//! assert_eq!(game.status(), GameStatus::Won);
+//! assert_eq!(game.lurd(), "R");
+//! ```
+
+mod board;
+mod direction;
+mod game;
+
+pub mod config;
+pub mod deadlock;
+pub mod editor;
+pub mod generate;
+pub mod literal;
+pub mod pack;
+pub mod records;
+pub mod render;
+pub mod replay;
+pub mod session;
+pub mod solver;
+pub mod theme;
+pub mod validate;
+pub mod xsb;
+
+pub use board::{Board, Coord, Index, Layer, Obj, Tile, Vec2D};
+pub use direction::Direction;
+pub use game::{format_duration, Game, GameStatus, Hint};
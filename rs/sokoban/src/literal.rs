@@ -0,0 +1,94 @@
+//! Writing boards as the Rust constants and array literals used for the
+//! built-in level:
+//!
+//! ```text
+//! const LEVEL_WIDTH: usize = 3;
+//! const LEVEL_HEIGHT: usize = 1;
+//!
+//! #[rustfmt::skip]
+//! static TILE_LAYER: [Tile; LEVEL_WIDTH * LEVEL_HEIGHT] = {
+//!     #[allow(non_snake_case)]
+//!     let (o, H, X) = (Tile::None, Tile::Wall, Tile::Goal);
+//!
+//!     [o,o,X]
+//! };
+//! ```
+
+use crate::{Board, Obj, Tile};
+
+#[must_use]
+fn tile_name(tile: Tile) -> char {
+    match tile {
+        Tile::None => 'o',
+        Tile::Wall => 'H',
+        Tile::Goal => 'X',
+    }
+}
+
+#[must_use]
+fn obj_name(obj: Obj) -> char {
+    match obj {
+        Obj::None => 'o',
+        Obj::Player => 'P',
+        Obj::Box => 'B',
+    }
+}
+
+/// Lays out `names` as an array literal, one board row per line.
+#[must_use]
+fn layer(board: &Board, names: &[char]) -> String {
+    let rows: Vec<String> = names
+        .chunks(board.width.max(1))
+        .map(|row| {
+            row.iter()
+                .map(char::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .collect();
+
+    format!("    [{}]", rows.join(",\n     "))
+}
+
+#[must_use]
+fn static_item(name: &str, ty: &str, board: &Board, bindings: &str, names: &[char]) -> String {
+    format!(
+        "#[rustfmt::skip]\n\
+         static {}: [{}; LEVEL_WIDTH * LEVEL_HEIGHT] = {{\n    \
+             #[allow(non_snake_case)]\n    \
+             {}\n\n\
+         {}\n\
+         }};\n",
+        name,
+        ty,
+        bindings,
+        layer(board, names)
+    )
+}
+
+/// Writes `board` as the `LEVEL_WIDTH` and `LEVEL_HEIGHT` constants and
+/// the `TILE_LAYER` and `OBJECT_LAYER` statics, ready to be pasted in place
+/// of the built-in level.
+#[must_use]
+pub fn to_literal(board: &Board) -> String {
+    let tiles: Vec<char> = board.tiles.iter().map(|&t| tile_name(t)).collect();
+    let objects: Vec<char> = board.objects.iter().map(|&o| obj_name(o)).collect();
+
+    format!(
+        "const LEVEL_WIDTH: usize = {};\nconst LEVEL_HEIGHT: usize = {};\n\n",
+        board.width, board.height
+    ) + &static_item(
+        "TILE_LAYER",
+        "Tile",
+        board,
+        "let (o, H, X) = (Tile::None, Tile::Wall, Tile::Goal);",
+        &tiles,
+    ) + "\n"
+        + &static_item(
+            "OBJECT_LAYER",
+            "Obj",
+            board,
+            "let (o, P, B) = (Obj::None, Obj::Player, Obj::Box);",
+            &objects,
+        )
+}
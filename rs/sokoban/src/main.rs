@@ -1,236 +1,734 @@
-#![feature(custom_attribute)]
-#![feature(stmt_expr_attributes)]
+mod cli;
+mod screen;
+mod term;
+
+use screen::Renderer;
+use sokoban::editor::Editor;
+use sokoban::generate::{self, Rng};
+use sokoban::pack::{self, LevelPack};
+use sokoban::records::{Improvements, Record, Records};
+use sokoban::render::Frame;
+use sokoban::session::Session;
+use sokoban::theme::{ColorMode, Theme};
+use sokoban::validate::Problem;
+use sokoban::{config, literal, replay, solver, validate, xsb};
+use sokoban::{format_duration, Board, Direction, Game, GameStatus, Index, Obj, Tile};
+use std::time::Duration;
+use term::Key;
+
+const LEVEL_WIDTH: usize = 8;
+const LEVEL_HEIGHT: usize = 8;
+
+/// Where the `x` key writes the current position.
+const EXPORT_PATH: &str = "position.xsb";
+
+/// Hints must come quickly, so the solver gives up sooner than on request.
+const HINT_LIMITS: solver::Limits = solver::Limits {
+    max_nodes: 500_000,
+    max_time: Duration::from_secs(10),
+};
 
-use std::io;
-use std::io::Read;
+#[rustfmt::skip]
+static TILE_LAYER: [Tile; LEVEL_WIDTH * LEVEL_HEIGHT] = {
+    #[allow(non_snake_case)]
+    let (o, H, X) = (Tile::None, Tile::Wall, Tile::Goal);
 
-#[must_use] // ANNOYANCE: not default
-#[repr(u8)] // ANNOYANCE: ugly syntax compared to enum class
-#[derive(PartialEq, Copy, Clone)]
-enum Tile {
-    None,
-    Wall,
-    Goal,
-}
+    [H,H,H,H,H,H,H,H,
+     H,H,o,o,o,o,o,H,
+     H,o,o,o,o,o,o,H,
+     H,o,o,o,o,o,o,H,
+     H,o,o,o,H,o,X,H,
+     H,o,o,o,o,o,X,H,
+     H,o,o,o,X,X,X,H,
+     H,H,H,H,H,H,H,H]
+};
+
+#[rustfmt::skip]
+static OBJECT_LAYER: [Obj; LEVEL_WIDTH * LEVEL_HEIGHT] = {
+    #[allow(non_snake_case)]
+    let (o, P, B) = (Obj::None, Obj::Player, Obj::Box);
+
+    [o,o,o,o,o,o,o,o,
+     o,o,o,o,o,o,o,o,
+     o,o,B,B,o,o,o,o,
+     o,o,B,o,B,o,o,o,
+     o,o,o,o,o,o,o,o,
+     o,o,o,o,B,o,o,o,
+     o,P,o,o,o,o,o,o,
+     o,o,o,o,o,o,o,o]
+};
 
 #[must_use]
-#[repr(u8)]
-#[derive(PartialEq, Copy, Clone)]
-enum Obj {
-    None,
-    Player,
-    Box,
+enum Outcome {
+    Next,
+    Restart,
+    Quit,
+    /// Play the level at this index of the pack.
+    Play(usize),
 }
 
-const BOARD_WIDTH: usize = 8;
-const BOARD_HEIGHT: usize = 8;
-
-type Layer<T> = [T; BOARD_WIDTH * BOARD_HEIGHT];
+/// Shows the end-of-level summary, including the level's records, and
+/// asks what to do next.
+fn finish_level(
+    renderer: &mut Renderer,
+    game: &Game,
+    record: &Record,
+    improvements: &Improvements,
+) -> Outcome {
+    let mut new_bests = Vec::new();
+    if improvements.moves {
+        new_bests.push("moves");
+    }
+    if improvements.pushes {
+        new_bests.push("pushes");
+    }
+    if improvements.time {
+        new_bests.push("time");
+    }
 
-type Index = usize;
-type Coord = usize;
-type Vec2D = (Coord, Coord);
+    loop {
+        let mut frame = Frame::new();
+        game.board().draw(&mut frame, renderer.theme());
+        frame.blank();
+        game.draw_summary(&mut frame);
+        frame.blank();
+        if improvements.any() {
+            frame.text(&format!("New best {}!", new_bests.join(", ")));
+        }
+        if let Some(summary) = record.summary() {
+            frame.text(&format!("Best:     {}", summary));
+        }
+        frame.text(&format!("Attempts: {}", record.attempts));
+        frame.blank();
+        frame.text("[n]ext level, [r]etry, [q]uit");
+        renderer.draw(frame);
+
+        match term::read_key() {
+            Key::Char('n') | Key::Enter => break Outcome::Next,
+            Key::Char('r') => break Outcome::Restart,
+            Key::Char('q') | Key::Interrupt | Key::Eof => break Outcome::Quit,
+            _ => {}
+        }
+    }
+}
 
+/// Writes the current position to `EXPORT_PATH`, returning a message to
+/// show the player.
 #[must_use]
-const fn to_index((x, y): Vec2D) -> Index {
-    (y * BOARD_WIDTH) + x
+fn export_position(game: &Game) -> String {
+    match std::fs::write(EXPORT_PATH, xsb::to_xsb(game.board())) {
+        Ok(()) => format!("Position written to {}", EXPORT_PATH),
+        Err(e) => format!("Couldn't write {}: {}", EXPORT_PATH, e),
+    }
 }
 
-#[must_use]
-const fn to_vec2d(i: Index) -> Vec2D {
-    (i % BOARD_WIDTH, i / BOARD_WIDTH)
+/// Adds a finished attempt to the records. Failing to save them isn't
+/// worth interrupting the game for, so errors are ignored.
+fn record_attempt(records: &mut Records, board: &Board, game: &Game) -> Improvements {
+    let improvements = records.add(board, game);
+    let _ = records.save();
+    improvements
 }
 
-#[must_use]
-fn tile_char(tile: Tile) -> char {
-    // ANNOYANCE: Can't be `const fn`
-    match tile {
-        Tile::None => ' ',
-        Tile::Wall => '▒',
-        Tile::Goal => '○',
+/// Plays `game` on the current level of `pack`, marking the level as
+/// solved and updating `records` when the player completes it.
+fn play_level(
+    renderer: &mut Renderer,
+    pack: &mut LevelPack,
+    records: &mut Records,
+    game: &mut Game,
+) -> Outcome {
+    let level = pack.current_level();
+    let mut message: Option<String> = None;
+
+    loop {
+        let mut frame = Frame::new();
+        frame.text(&level.title);
+        frame.blank();
+        game.draw(&mut frame, renderer.theme());
+        if let Some(message) = message.take() {
+            frame.blank();
+            frame.text(&message);
+        }
+        frame.blank();
+        frame.text("[arrows] move, [u/U] undo/redo, [h] hint, [g] go to");
+        frame.text("[r] restart, [l] levels, [x] export, [q] quit");
+        renderer.draw(frame);
+
+        let input = term::read_key();
+
+        #[rustfmt::skip]
+        let _ = match input {
+            Key::Char('w') | Key::Up    => game.move_player(Direction::Up),
+            Key::Char('s') | Key::Down  => game.move_player(Direction::Down),
+            Key::Char('a') | Key::Left  => game.move_player(Direction::Left),
+            Key::Char('d') | Key::Right => game.move_player(Direction::Right),
+            Key::Char('u')              => game.undo(),
+            Key::Char('U')              => game.redo(),
+            _                           => false,
+        };
+
+        if game.status() == GameStatus::Won {
+            let improvements = record_attempt(records, &level.board, game);
+            let record = records.get(&level.board).cloned().unwrap_or_default();
+            pack.mark_solved();
+            break finish_level(renderer, game, &record, &improvements);
+        }
+        let outcome = match input {
+            Key::Char('r') => Outcome::Restart,
+            // Cancelling the selection carries on with the current game.
+            Key::Char('l') => match select_level(renderer, pack, records) {
+                Some(i) => Outcome::Play(i),
+                None => continue,
+            },
+            Key::Char('x') => {
+                message = Some(export_position(game));
+                continue;
+            }
+            Key::Char('h') => {
+                message = show_hint(game);
+                continue;
+            }
+            Key::Char('g') => {
+                if let Some(target) = choose_target(renderer, &level.title, game) {
+                    let _ = game.go_to(target);
+                }
+                continue;
+            }
+            // Unfinished games are saved on quitting rather than abandoned,
+            // and only count as an attempt once finished or thrown away.
+            Key::Char('q') | Key::Interrupt | Key::Eof => break Outcome::Quit,
+            _ => continue,
+        };
+
+        if game.moves() > 0 {
+            let _ = record_attempt(records, &level.board, game);
+        }
+        break outcome;
     }
 }
 
+/// Lets the player pick a cell to walk to, showing the cells they can reach.
+/// Returns `None` if they cancel.
 #[must_use]
-fn obj_char(obj: Obj, tile: Tile) -> char {
-    // ANNOYANCE: Can't be `const fn`
-    match obj {
-        Obj::None => tile_char(tile),
-        Obj::Player => '☻',
-        Obj::Box => {
-            if tile == Tile::Goal {
-                '◙'
-            } else {
-                '■'
+fn choose_target(renderer: &mut Renderer, title: &str, game: &Game) -> Option<Index> {
+    let reachable = game.reachable();
+    let mut cursor = game.player_index();
+    let mut message = "[arrows] choose, [enter] go, [g] cancel";
+
+    loop {
+        let mut frame = Frame::new();
+        frame.text(title);
+        frame.blank();
+        // The cursor stands out by being drawn the other way round.
+        game.draw_marked(&mut frame, renderer.theme(), |i| {
+            reachable[i] != (i == cursor)
+        });
+        frame.blank();
+        frame.text(message);
+        renderer.draw(frame);
+
+        let direction = match term::read_key() {
+            Key::Char('w') | Key::Up => Direction::Up,
+            Key::Char('s') | Key::Down => Direction::Down,
+            Key::Char('a') | Key::Left => Direction::Left,
+            Key::Char('d') | Key::Right => Direction::Right,
+            Key::Enter if reachable[cursor] => break Some(cursor),
+            Key::Enter => {
+                message = "Can't get there without pushing a box.";
+                continue;
             }
-        } // ANNOYANCE: verbose, ugly
+            Key::Char('g') | Key::Char('q') | Key::Escape | Key::Interrupt | Key::Eof => {
+                break None
+            }
+            _ => continue,
+        };
+
+        if let Some(n) = game.board().neighbour(cursor, direction) {
+            cursor = n;
+        }
     }
 }
 
+/// Asks the solver for the next move, returning a message if there is none.
 #[must_use]
-struct Board {
-    tiles: Layer<Tile>,
-    objects: Layer<Obj>,
+fn show_hint(game: &mut Game) -> Option<String> {
+    if game.is_deadlocked() {
+        return Some(String::from("No hint: this position can't be solved."));
+    }
+
+    match game.hint(&HINT_LIMITS) {
+        Ok(Some(hint)) => {
+            let name = |direction: Direction| format!("{:?}", direction).to_lowercase();
+            let (box_index, push) = hint.push;
+            let (x, y) = game.board().to_vec2d(box_index);
+            Some(format!(
+                "Hint: move {}. Next, push the box at {},{} {}.",
+                name(hint.direction),
+                x + 1,
+                y + 1,
+                name(push)
+            ))
+        }
+        Ok(None) => None,
+        Err(e) => Some(format!("No hint: {}.", e)),
+    }
 }
 
-impl Board {
-    fn print(&self) {
-        for y in 0..BOARD_HEIGHT {
-            for x in 0..BOARD_WIDTH {
-                let i = to_index((x, y));
-                print!("{}", obj_char(self.objects[i], self.tiles[i]));
+/// Shows the list of levels and lets the player pick one, returning `None`
+/// if they cancel.
+#[must_use]
+fn select_level(renderer: &mut Renderer, pack: &LevelPack, records: &Records) -> Option<usize> {
+    let mut selected = pack.current;
+
+    loop {
+        let mut frame = Frame::new();
+        pack.draw(&mut frame, selected, records);
+        frame.blank();
+        frame.text("[w/s] select, [enter] play, [q] cancel");
+        renderer.draw(frame);
+
+        match term::read_key() {
+            Key::Char('w') | Key::Up => selected = selected.saturating_sub(1),
+            Key::Char('s') | Key::Down => {
+                selected = (selected + 1).min(pack.levels.len() - 1);
             }
-            println!();
+            Key::Enter => break Some(selected),
+            Key::Char('q') | Key::Escape | Key::Interrupt | Key::Eof => break None,
+            _ => {}
         }
     }
+}
 
-    #[must_use]
-    fn find_player(&self) -> Coord {
-        self.objects.iter().position(|x| *x == Obj::Player).unwrap()
+/// Offers to continue the saved session, if it was for a level of `pack`
+/// that hasn't changed since. Declining it ends the saved attempt.
+#[must_use]
+fn resume_session(
+    renderer: &mut Renderer,
+    pack: &mut LevelPack,
+    records: &mut Records,
+    path: Option<&str>,
+) -> Option<Game> {
+    let session = Session::load()?;
+    let level = pack.levels.get(session.level)?;
+
+    if session.pack.as_deref() != pack_id(path).as_deref() || level.board != session.board {
+        return None;
+    }
+    let game = session.to_game()?;
+
+    let mut frame = Frame::new();
+    frame.text(&level.title);
+    frame.blank();
+    game.draw(&mut frame, renderer.theme());
+    frame.blank();
+    frame.text(&format!(
+        "Resume saved game ({} moves, {})? [y/n]",
+        game.moves(),
+        format_duration(game.elapsed())
+    ));
+    renderer.draw(frame);
+
+    match term::read_key() {
+        Key::Char('y') | Key::Enter => {
+            pack.current = session.level;
+            Some(game)
+        }
+        _ => {
+            let _ = record_attempt(records, &level.board, &game);
+            None
+        }
     }
+}
 
-    #[must_use]
-    fn count_goals(&self) -> usize {
-        self.tiles.iter().filter(|x| **x == Tile::Goal).count() // TODO: why all the stars?
+/// Saves `game` to be resumed on the next launch, or forgets the saved
+/// session if there is nothing left to resume.
+fn save_session(pack: &LevelPack, path: Option<&str>, game: &Game) -> std::io::Result<()> {
+    if game.status() == GameStatus::Won || game.moves() == 0 {
+        Session::remove();
+        return Ok(());
     }
+
+    let board = pack.current_level().board.clone();
+    Session::new(pack_id(path), pack.current, board, game).save()
 }
 
+/// Identifies the pack at `path` in saved sessions.
 #[must_use]
-struct Game {
-    board: Board,
-    player_index: Coord,
-    goals_left: usize,
+fn pack_id(path: Option<&str>) -> Option<String> {
+    let path = path?;
+    let absolute = std::fs::canonicalize(path).unwrap_or_else(|_| path.into());
+    Some(absolute.to_string_lossy().into_owned())
 }
 
-impl Game {
-    #[must_use]
-    fn new(board: Board) -> Game {
-        Game {
-            player_index: board.find_player(),
-            goals_left: board.count_goals(),
-            board,
+/// Size of the room the editor starts with for new levels.
+const NEW_LEVEL_SIZE: (usize, usize) = (7, 7);
+
+/// Runs the level editor on the level in `path`, which is only written if
+/// the level passes validation.
+fn edit_level(renderer: &mut Renderer, path: &str, mut editor: Editor) {
+    let mut messages: Vec<String> = Vec::new();
+    let mut saved = std::path::Path::new(path).exists();
+    let mut quitting = false;
+
+    loop {
+        let board = editor.board();
+        let (x, y) = editor.cursor();
+
+        let mut frame = Frame::new();
+        frame.text(&format!("Editing {}", path));
+        frame.blank();
+        editor.draw(&mut frame, renderer.theme());
+        frame.blank();
+        frame.text(&format!(
+            "Size: {}x{}, cursor: {},{}",
+            board.width,
+            board.height,
+            x + 1,
+            y + 1
+        ));
+        for message in messages.drain(..) {
+            frame.text(&message);
+        }
+        frame.blank();
+        frame.text("[arrows] move, [# . $ * @ + space] place, [< > ^ v] resize");
+        frame.text("[enter] save, [q] quit");
+        renderer.draw(frame);
+
+        let (width, height) = (board.width, board.height);
+        let before = xsb::to_xsb(board);
+        let key = term::read_key();
+        let quit = matches!(
+            key,
+            Key::Char('q') | Key::Escape | Key::Interrupt | Key::Eof
+        );
+
+        match key {
+            Key::Char('w') | Key::Up => editor.move_cursor(Direction::Up),
+            Key::Char('s') | Key::Down => editor.move_cursor(Direction::Down),
+            Key::Char('a') | Key::Left => editor.move_cursor(Direction::Left),
+            Key::Char('d') | Key::Right => editor.move_cursor(Direction::Right),
+            Key::Char('<') => editor.resize(width - 1, height),
+            Key::Char('>') => editor.resize(width + 1, height),
+            Key::Char('^') => editor.resize(width, height - 1),
+            Key::Char('v') => editor.resize(width, height + 1),
+            Key::Backspace => {
+                let _ = editor.place(' ');
+            }
+            Key::Char(c) if !quit => {
+                let _ = editor.place(c);
+            }
+            Key::Enter => {
+                let problems = validate::validate(editor.board());
+                if !validate::is_valid(&problems) {
+                    messages.push("Not saved:".to_string());
+                } else {
+                    match std::fs::write(path, xsb::to_xsb(editor.board())) {
+                        Ok(()) => {
+                            messages.push(format!("Saved to {}", path));
+                            saved = true;
+                        }
+                        Err(e) => messages.push(format!("Couldn't write {}: {}", path, e)),
+                    }
+                }
+                messages.extend(problems.iter().map(|p| format!("  {}", p)));
+            }
+            _ => {}
+        }
+
+        if xsb::to_xsb(editor.board()) != before {
+            saved = false;
         }
-    }
 
-    #[must_use]
-    fn obj_at(&mut self, i: Index) -> &mut Obj {
-        &mut self.board.objects[i]
+        if quit {
+            if saved || quitting {
+                break;
+            }
+            messages.push("Unsaved changes; quit again to discard them".to_string());
+        }
+        quitting = quit;
     }
+}
 
-    #[must_use]
-    fn tile_at(&mut self, i: Index) -> &mut Tile {
-        &mut self.board.tiles[i]
+/// Loads the level to edit from `path`, or starts a new one if there is
+/// no such file. Unplayable levels are loaded too, so that they can be
+/// fixed.
+fn load_editor(path: &str) -> Editor {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let (width, height) = NEW_LEVEL_SIZE;
+            return Editor::empty(width, height);
+        }
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1);
+        }
+    };
+
+    match xsb::parse_layout(&text) {
+        Ok(board) => Editor::new(board),
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1);
+        }
     }
+}
 
-    #[must_use]
-    fn move_box(&mut self, pos: Vec2D, (ox, oy): (isize, isize)) -> bool {
-        let (px, py) = pos;
-        let source = to_index((px, py));
-        let target = to_index(((px as isize + ox) as usize, (py as isize + oy) as usize));
+/// Prints the problems with every level in `path` as JSON, exiting with
+/// status 1 if any level is invalid. Levels that can't be read are reported
+/// like any other problem.
+fn validate_levels(path: Option<&str>) -> ! {
+    let levels: Vec<(String, Vec<Problem>)> = match path {
+        Some(path) => {
+            let text = std::fs::read_to_string(path).unwrap_or_else(|e| {
+                eprintln!("{}: {}", path, e);
+                std::process::exit(2);
+            });
+
+            let entries = pack::entries(&text);
+            if entries.is_empty() {
+                eprintln!("{}: {}", path, pack::PackError::NoLevels);
+                std::process::exit(2);
+            }
 
-        if *self.tile_at(target) == Tile::Wall || *self.obj_at(target) != Obj::None {
-            return false;
+            entries
+                .into_iter()
+                .map(|entry| {
+                    let problems = validate::validate_text(&entry.rows);
+                    (entry.title, problems)
+                })
+                .collect()
         }
+        None => load_pack(None)
+            .levels
+            .into_iter()
+            .map(|level| {
+                let problems = validate::validate(&level.board);
+                (level.title, problems)
+            })
+            .collect(),
+    };
+
+    let valid = levels
+        .iter()
+        .all(|(_, problems)| validate::is_valid(problems));
+    let levels: Vec<String> = levels
+        .iter()
+        .enumerate()
+        .map(|(i, (title, problems))| validate::level_json(i + 1, title, problems))
+        .collect();
+
+    println!(
+        "{{\"valid\": {}, \"levels\": [\n  {}\n]}}",
+        valid,
+        levels.join(",\n  ")
+    );
+    std::process::exit(if valid { 0 } else { 1 });
+}
 
-        if *self.tile_at(source) == Tile::Goal {
-            self.goals_left += 1;
+/// Prints a new level as XSB, titled with its seed so it can be made again.
+fn generate_level(options: &cli::Options) -> ! {
+    let seed = options.seed.clone().unwrap_or_else(|| {
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH);
+        now.map_or(0, |d| d.as_secs()).to_string()
+    });
+
+    match generate::generate(&options.params, &mut Rng::from_text(&seed)) {
+        Ok(level) => {
+            print!("{}", xsb::to_xsb(&level.board));
+            println!("Title: Seed {}", seed);
+            println!(
+                "Comment: {}, solved in {} moves and {} pushes after searching {} positions",
+                level.difficulty(),
+                level.solution.lurd.len(),
+                level.solution.pushes,
+                level.solution.nodes
+            );
+            std::process::exit(0);
         }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
 
-        if *self.tile_at(target) == Tile::Goal {
-            self.goals_left -= 1;
+fn load_pack(path: Option<&str>) -> LevelPack {
+    let path = match path {
+        Some(path) => path,
+        None => {
+            return LevelPack::single(Board {
+                width: LEVEL_WIDTH,
+                height: LEVEL_HEIGHT,
+                tiles: TILE_LAYER.to_vec(),
+                objects: OBJECT_LAYER.to_vec(),
+            })
         }
+    };
+
+    let result = std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| LevelPack::parse(&text).map_err(|e| e.to_string()));
 
-        self.board.objects.swap(target, source);
-        true
+    match result {
+        Ok(pack) => pack,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1);
+        }
     }
+}
 
-    fn move_player(&mut self, offset: (isize, isize)) -> bool {
-        let (px, py) = to_vec2d(self.player_index);
-        let (ox, oy) = offset;
-        // TODO: can the many casts be avoided?
-        let (tx, ty): (isize, isize) = (px as isize + ox, py as isize + oy);
-        let target_vec2d = (tx as usize, ty as usize);
-        let target = to_index(target_vec2d);
+/// Picks the theme from the command line or, failing that, the config file.
+fn load_theme(options: &cli::Options) -> Theme {
+    let config = config::Config::load().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        config::Config::default()
+    });
 
-        let couldnt_push_box =
-            *self.obj_at(target) == Obj::Box && !self.move_box(target_vec2d, offset);
+    let name = options.theme.as_ref().or(config.theme.as_ref());
+    let mut theme = match name {
+        Some(name) => Theme::by_name(name).unwrap_or_else(|| {
+            eprintln!(
+                "unknown theme {:?} (available: {})",
+                name,
+                Theme::names().join(", ")
+            );
+            std::process::exit(2);
+        }),
+        None => Theme::default(),
+    };
+
+    theme.colors = match options.color.as_ref().or(config.color.as_ref()) {
+        Some(mode) => ColorMode::parse(mode).unwrap_or_else(|| {
+            eprintln!("unknown color mode {:?} (use none, 256 or truecolor)", mode);
+            std::process::exit(2);
+        }),
+        None => ColorMode::detect(),
+    };
+
+    theme
+}
 
-        if *self.tile_at(target) == Tile::Wall || couldnt_push_box {
-            return false;
+fn main() {
+    let options = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
         }
+    };
 
-        self.board.objects.swap(target, self.player_index);
-        self.player_index = target;
-        true
+    if options.validate {
+        validate_levels(options.path.as_deref());
     }
 
-    fn print(&self) {
-        self.board.print();
-        println!("\nGoals left: {}\n", self.goals_left);
+    if options.generate {
+        generate_level(&options);
     }
-}
 
-static TILE_LAYER: Layer<Tile> = {
-    #[allow(non_snake_case)]
-    let (o, H, X) = (Tile::None, Tile::Wall, Tile::Goal);
+    if let Some(path) = &options.edit {
+        let editor = load_editor(path);
+        let theme = load_theme(&options);
+        let _raw_mode = term::RawMode::enable();
+        let mut renderer = Renderer::new(theme);
+        edit_level(&mut renderer, path, editor);
+        return;
+    }
 
-    #[rustfmt::skip]
-    [H,H,H,H,H,H,H,H,
-     H,H,o,o,o,o,o,H,
-     H,o,o,o,o,o,o,H,
-     H,o,o,o,o,o,o,H,
-     H,o,o,o,H,o,X,H,
-     H,o,o,o,o,o,X,H,
-     H,o,o,o,X,X,X,H,
-     H,H,H,H,H,H,H,H]
-};
+    let mut pack = load_pack(options.path.as_deref());
 
-static OBJECT_LAYER: Layer<Obj> = {
-    #[allow(non_snake_case)]
-    let (o, P, B) = (Obj::None, Obj::Player, Obj::Box);
+    if options.level >= pack.levels.len() {
+        eprintln!("level {} does not exist", options.level + 1);
+        std::process::exit(2);
+    }
+    pack.current = options.level;
 
-    #[rustfmt::skip]
-    [o,o,o,o,o,o,o,o,
-     o,o,o,o,o,o,o,o,
-     o,o,B,B,o,o,o,o,
-     o,o,B,o,B,o,o,o,
-     o,o,o,o,o,o,o,o,
-     o,o,o,o,B,o,o,o,
-     o,P,o,o,o,o,o,o,
-     o,o,o,o,o,o,o,o]
-};
+    if let Some(format) = options.export {
+        let mut board = pack.current_level().board.clone();
 
-#[must_use]
-fn restart() -> bool {
-    let mut game = Game::new(Board {
-        tiles: TILE_LAYER,
-        objects: OBJECT_LAYER,
-    });
+        if let Some(lurd) = &options.replay {
+            let report = replay::replay(&board, lurd);
+            if let Some(error) = &report.error {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+            board = report.board;
+        }
 
-    loop {
-        let _ = std::process::Command::new("clear").status();
-        game.print();
+        match format {
+            cli::Format::Xsb => print!("{}", xsb::to_xsb(&board)),
+            cli::Format::Rust => print!("{}", literal::to_literal(&board)),
+        }
+        std::process::exit(0);
+    }
 
-        let input = io::stdin().lock().bytes().nth(0).unwrap().unwrap() as char;
+    if let Some(lurd) = &options.replay {
+        let report = replay::replay(&pack.current_level().board, lurd);
+        println!("{}", report);
+        std::process::exit(if report.valid() && report.solved {
+            0
+        } else {
+            1
+        });
+    }
 
-        #[rustfmt::skip]
-        match input as char {
-            'w' => { game.move_player(( 0, -1)); }
-            's' => { game.move_player(( 0,  1)); }
-            'a' => { game.move_player((-1,  0)); }
-            'd' => { game.move_player(( 1,  0)); }
-            _   => {}
+    if options.solve {
+        match solver::solve(&pack.current_level().board, &options.limits) {
+            Ok(solution) => {
+                println!("{}", solution.lurd);
+                println!(
+                    "moves: {}, pushes: {}, nodes: {}",
+                    solution.lurd.len(),
+                    solution.pushes,
+                    solution.nodes
+                );
+                std::process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
         }
+    }
 
-        if input == 'r' {
-            break true;
-        }
-        if input == 'q' {
-            break false;
+    let theme = load_theme(&options);
+    let _raw_mode = term::RawMode::enable();
+    let mut renderer = Renderer::new(theme);
+    let mut records = Records::load();
+
+    let path = options.path.as_deref();
+    let mut game = resume_session(&mut renderer, &mut pack, &mut records, path)
+        .unwrap_or_else(|| Game::new(pack.current_level().board.clone()));
+
+    loop {
+        match play_level(&mut renderer, &mut pack, &mut records, &mut game) {
+            Outcome::Restart => {}
+            Outcome::Quit => {
+                drop(renderer);
+                if let Err(e) = save_session(&pack, path, &game) {
+                    eprintln!("couldn't save the game: {}", e);
+                }
+                break;
+            }
+            Outcome::Next => {
+                if !pack.advance() {
+                    // Go back for any levels skipped on the way.
+                    match pack.solved.iter().position(|&solved| !solved) {
+                        Some(i) => pack.current = i,
+                        None => {
+                            drop(renderer);
+                            Session::remove();
+                            println!("All levels solved!");
+                            break;
+                        }
+                    }
+                }
+            }
+            Outcome::Play(i) => pack.current = i,
         }
-    }
-}
 
-fn main() {
-    while restart() {}
+        game = Game::new(pack.current_level().board.clone());
+    }
 }
@@ -0,0 +1,259 @@
+//! Level collections, as distributed in common `.txt`/`.sok` packs: XSB
+//! boards separated by blank lines, with free-form text or `Title:` lines
+//! naming each level.
+
+use crate::records::Records;
+use crate::render::Frame;
+use crate::xsb::{self, ParseError};
+use crate::{Board, Obj, Tile};
+use std::fmt;
+
+#[must_use]
+pub struct Level {
+    pub title: String,
+    pub board: Board,
+}
+
+#[must_use]
+pub struct LevelPack {
+    pub levels: Vec<Level>,
+    pub solved: Vec<bool>,
+    pub current: usize,
+}
+
+#[must_use]
+#[derive(Debug, PartialEq)]
+pub enum PackError {
+    NoLevels,
+    Level { number: usize, error: ParseError },
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PackError::NoLevels => write!(f, "file contains no levels"),
+            PackError::Level { number, error } => write!(f, "level {}: {}", number, error),
+        }
+    }
+}
+
+impl std::error::Error for PackError {}
+
+/// Rows of a board start with a wall, possibly after some indentation, or
+/// are made of XSB glyphs only with at least one of them not floor, as in
+/// levels without a surrounding wall. Rows starting with a wall may hold
+/// unknown glyphs, which are reported when the board is read.
+#[must_use]
+fn is_board_line(line: &str) -> bool {
+    let empty = Some((Tile::None, Obj::None));
+    line.trim_start_matches([' ', '-', '_']).starts_with('#')
+        || (line.chars().all(|c| xsb::glyph_to_cell(c).is_some())
+            && line.chars().any(|c| xsb::glyph_to_cell(c) != empty))
+}
+
+/// Rows of nothing but floor, such as `---`, which only belong to a board
+/// next to other rows of it.
+#[must_use]
+fn is_floor_line(line: &str) -> bool {
+    line.contains(['-', '_']) && line.chars().all(|c| matches!(c, ' ' | '-' | '_'))
+}
+
+/// Lines such as `Author: Someone` that describe a level rather than name it.
+#[must_use]
+fn is_metadata(line: &str) -> bool {
+    match line.split_once(':') {
+        Some((key, _)) => !key.is_empty() && key.chars().all(|c| c.is_ascii_alphabetic()),
+        None => false,
+    }
+}
+
+#[must_use]
+fn title_of(line: &str) -> Option<&str> {
+    let lower = line.to_ascii_lowercase();
+    if lower.starts_with("title:") {
+        Some(line["title:".len()..].trim())
+    } else {
+        None
+    }
+}
+
+/// A level of a pack whose board hasn't been read yet.
+#[must_use]
+pub struct Entry {
+    pub title: String,
+    /// The rows of the board, one per line.
+    pub rows: String,
+    /// The line of the pack the board starts on, counting from 1.
+    pub first_line: usize,
+}
+
+impl Entry {
+    fn new(index: usize, rows: &[&str], first_line: usize, caption: Option<String>) -> Entry {
+        let title = match caption {
+            Some(caption) if !caption.is_empty() => caption,
+            _ => format!("Level {}", index + 1),
+        };
+
+        Entry {
+            title,
+            rows: rows.join("\n"),
+            first_line,
+        }
+    }
+}
+
+/// Splits a pack into its levels, named as described for
+/// [`LevelPack::parse`], so that they can be read one by one.
+pub fn entries(text: &str) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut rows: Vec<&str> = Vec::new();
+    let mut first_line = 0;
+    let mut caption: Option<String> = None;
+    // Whether the lines since the last board have all been non-blank.
+    let mut below_board = false;
+
+    let lines: Vec<&str> = text.lines().map(|l| l.trim_end()).collect();
+
+    for (i, &line) in lines.iter().enumerate() {
+        let starts_board = || {
+            let next = lines[i..].iter().find(|l| !is_floor_line(l));
+            next.is_some_and(|l| is_board_line(l))
+        };
+
+        if is_board_line(line) || (is_floor_line(line) && (!rows.is_empty() || starts_board())) {
+            if rows.is_empty() {
+                first_line = i + 1;
+            }
+            rows.push(line);
+            continue;
+        }
+
+        if !rows.is_empty() {
+            entries.push(Entry::new(entries.len(), &rows, first_line, caption.take()));
+            rows.clear();
+            below_board = true;
+        }
+
+        let line = line.trim();
+        if line.is_empty() {
+            below_board = false;
+            continue;
+        }
+
+        match (title_of(line), entries.last_mut()) {
+            (Some(title), Some(entry)) if below_board => entry.title = title.to_string(),
+            (Some(title), _) => caption = Some(title.to_string()),
+            (None, _) if is_metadata(line) => {}
+            (None, _) => caption = Some(line.trim_start_matches(';').trim().to_string()),
+        }
+    }
+
+    if !rows.is_empty() {
+        entries.push(Entry::new(entries.len(), &rows, first_line, caption));
+    }
+
+    entries
+}
+
+impl LevelPack {
+    pub fn single(board: Board) -> LevelPack {
+        LevelPack {
+            levels: vec![Level {
+                title: String::from("Level 1"),
+                board,
+            }],
+            solved: vec![false],
+            current: 0,
+        }
+    }
+
+    /// Parses a collection of levels. A level is named by a `Title:` line
+    /// directly below its board or, failing that, by the last `Title:` or
+    /// free-form line preceding it. Other `Key: value` lines are ignored.
+    pub fn parse(text: &str) -> Result<LevelPack, PackError> {
+        let entries = entries(text);
+        if entries.is_empty() {
+            return Err(PackError::NoLevels);
+        }
+
+        let levels = entries
+            .into_iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let board = xsb::parse_board(&entry.rows).map_err(|error| {
+                    let error = match error {
+                        ParseError::UnknownGlyph {
+                            line,
+                            column,
+                            glyph,
+                        } => ParseError::UnknownGlyph {
+                            line: line + entry.first_line - 1,
+                            column,
+                            glyph,
+                        },
+                        error => error,
+                    };
+
+                    PackError::Level {
+                        number: index + 1,
+                        error,
+                    }
+                })?;
+
+                Ok(Level {
+                    title: entry.title,
+                    board,
+                })
+            })
+            .collect::<Result<Vec<Level>, PackError>>()?;
+
+        Ok(LevelPack {
+            solved: vec![false; levels.len()],
+            levels,
+            current: 0,
+        })
+    }
+
+    pub fn current_level(&self) -> &Level {
+        &self.levels[self.current]
+    }
+
+    pub fn mark_solved(&mut self) {
+        self.solved[self.current] = true;
+    }
+
+    /// Moves on to the next level, returning `false` if the current level
+    /// was the last one.
+    #[must_use]
+    pub fn advance(&mut self) -> bool {
+        if self.current + 1 < self.levels.len() {
+            self.current += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Lists the levels, marking those solved now or in earlier sessions
+    /// along with their best results.
+    pub fn draw(&self, frame: &mut Frame, selected: usize, records: &Records) {
+        for (i, level) in self.levels.iter().enumerate() {
+            let record = records.get(&level.board);
+            let marker = if i == selected { '>' } else { ' ' };
+            let solved = self.solved[i] || record.is_some_and(|r| r.is_solved());
+            let best = match record.and_then(|r| r.summary()) {
+                Some(summary) => format!(" ({})", summary),
+                None => String::new(),
+            };
+
+            frame.text(&format!(
+                "{} {:3}. [{}] {}{}",
+                marker,
+                i + 1,
+                if solved { 'x' } else { ' ' },
+                level.title,
+                best
+            ));
+        }
+    }
+}
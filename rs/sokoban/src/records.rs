@@ -0,0 +1,210 @@
+//! Best scores per level, kept in `$XDG_DATA_HOME/sokoban/records`.
+//!
+//! Levels are identified by a hash of their layout, so records follow a
+//! level across packs and survive edits to its title. Each line of the file
+//! holds one level:
+//!
+//! ```text
+//! <hash> <attempts> <best moves> <best pushes> <best time in ms> <best LURD>
+//! ```
+//!
+//! with `-` for values not known until the level is solved. Lines that
+//! can't be read are skipped, so a damaged file loses at most those levels.
+
+use crate::{config, format_duration, xsb};
+use crate::{Board, Game, GameStatus};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// 64-bit FNV-1a.
+#[must_use]
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Hashes the layout of `board`.
+#[must_use]
+pub fn level_key(board: &Board) -> u64 {
+    fnv1a(xsb::to_xsb(board).as_bytes())
+}
+
+#[must_use]
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct Record {
+    pub attempts: usize,
+    pub best_moves: Option<usize>,
+    pub best_pushes: Option<usize>,
+    pub best_time: Option<Duration>,
+    /// The solution with the fewest moves.
+    pub best_lurd: Option<String>,
+}
+
+impl Record {
+    #[must_use]
+    pub fn is_solved(&self) -> bool {
+        self.best_moves.is_some()
+    }
+
+    /// Describes the best results, or `None` if the level is unsolved.
+    #[must_use]
+    pub fn summary(&self) -> Option<String> {
+        match (self.best_moves, self.best_pushes, self.best_time) {
+            (Some(moves), Some(pushes), Some(time)) => Some(format!(
+                "{} moves, {} pushes, {}",
+                moves,
+                pushes,
+                format_duration(time)
+            )),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    fn parse(fields: &[&str]) -> Option<Record> {
+        fn optional<T: std::str::FromStr>(field: &str) -> Option<Option<T>> {
+            match field {
+                "-" => Some(None),
+                _ => field.parse().ok().map(Some),
+            }
+        }
+
+        match fields {
+            [attempts, moves, pushes, time, lurd] => Some(Record {
+                attempts: attempts.parse().ok()?,
+                best_moves: optional(moves)?,
+                best_pushes: optional(pushes)?,
+                best_time: optional(time)?.map(Duration::from_millis),
+                best_lurd: optional(lurd)?,
+            }),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    fn to_fields(&self) -> String {
+        fn optional<T: ToString>(value: Option<T>) -> String {
+            value.map_or_else(|| "-".to_string(), |v| v.to_string())
+        }
+
+        format!(
+            "{} {} {} {} {}",
+            self.attempts,
+            optional(self.best_moves),
+            optional(self.best_pushes),
+            optional(self.best_time.map(|t| t.as_millis())),
+            optional(self.best_lurd.as_ref().filter(|l| !l.is_empty())),
+        )
+    }
+}
+
+/// Which of the best results a finished game improved on.
+#[must_use]
+#[derive(Default, Debug, PartialEq)]
+pub struct Improvements {
+    pub moves: bool,
+    pub pushes: bool,
+    pub time: bool,
+}
+
+impl Improvements {
+    #[must_use]
+    pub fn any(&self) -> bool {
+        self.moves || self.pushes || self.time
+    }
+}
+
+#[must_use]
+#[derive(Default)]
+pub struct Records {
+    levels: BTreeMap<u64, Record>,
+}
+
+impl Records {
+    #[must_use]
+    pub fn path() -> Option<PathBuf> {
+        config::data_dir().map(|dir| dir.join("records"))
+    }
+
+    /// Reads the records file. A missing or unreadable file yields no
+    /// records.
+    pub fn load() -> Records {
+        let text = Records::path().and_then(|path| std::fs::read_to_string(path).ok());
+        Records::parse(text.as_deref().unwrap_or(""))
+    }
+
+    pub fn parse(text: &str) -> Records {
+        let levels = text
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let (key, fields) = fields.split_first()?;
+                let key = u64::from_str_radix(key, 16).ok()?;
+                Some((key, Record::parse(fields)?))
+            })
+            .collect();
+
+        Records { levels }
+    }
+
+    /// Writes the records, replacing the file in one step so that an
+    /// interrupted save can't damage it.
+    pub fn save(&self) -> io::Result<()> {
+        let path = Records::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, self.to_text())?;
+        std::fs::rename(partial, path)
+    }
+
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        self.levels
+            .iter()
+            .map(|(key, record)| format!("{:016x} {}\n", key, record.to_fields()))
+            .collect()
+    }
+
+    pub fn get(&self, board: &Board) -> Option<&Record> {
+        self.levels.get(&level_key(board))
+    }
+
+    /// Counts a finished attempt at `board`, which started out as the
+    /// level, and keeps its results if `game` was won.
+    pub fn add(&mut self, board: &Board, game: &Game) -> Improvements {
+        let record = self.levels.entry(level_key(board)).or_default();
+        record.attempts += 1;
+
+        if game.status() != GameStatus::Won {
+            return Improvements::default();
+        }
+
+        let better = |best: Option<_>, value| best.is_none_or(|best| value < best);
+        let improvements = Improvements {
+            moves: better(record.best_moves, game.moves()),
+            pushes: better(record.best_pushes, game.pushes()),
+            time: record.best_time.is_none_or(|best| game.elapsed() < best),
+        };
+
+        if improvements.moves {
+            record.best_moves = Some(game.moves());
+            record.best_lurd = Some(game.lurd());
+        }
+        if improvements.pushes {
+            record.best_pushes = Some(game.pushes());
+        }
+        if improvements.time {
+            record.best_time = Some(game.elapsed());
+        }
+
+        improvements
+    }
+}
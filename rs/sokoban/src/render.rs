@@ -0,0 +1,113 @@
+//! Frames of text to draw, and the ANSI escape sequences that turn one
+//! frame into the next by rewriting only the cells that changed.
+
+/// A single character cell of a frame, `width` columns wide on screen.
+/// Highlighted cells are drawn in reverse video on a terminal.
+#[must_use]
+#[derive(Clone, PartialEq)]
+pub struct Cell {
+    pub text: String,
+    pub width: usize,
+    pub highlight: bool,
+}
+
+impl Cell {
+    pub fn new(c: char) -> Cell {
+        Cell {
+            text: c.to_string(),
+            width: 1,
+            highlight: false,
+        }
+    }
+
+    pub fn highlighted(self) -> Cell {
+        Cell {
+            highlight: true,
+            ..self
+        }
+    }
+}
+
+#[must_use]
+#[derive(Default)]
+pub struct Frame {
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Frame {
+    pub fn new() -> Frame {
+        Frame::default()
+    }
+
+    pub fn row(&mut self, cells: Vec<Cell>) {
+        self.rows.push(cells);
+    }
+
+    pub fn text(&mut self, line: &str) {
+        self.rows.push(line.chars().map(Cell::new).collect());
+    }
+
+    pub fn blank(&mut self) {
+        self.rows.push(Vec::new());
+    }
+
+    #[must_use]
+    pub fn to_plain_string(&self) -> String {
+        let mut out = String::new();
+        for row in &self.rows {
+            for cell in row {
+                out.push_str(&cell.text);
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Produces the escape sequences turning `previous` into `next` on a
+/// terminal showing `previous`.
+#[must_use]
+pub fn diff(previous: &Frame, next: &Frame) -> String {
+    let mut out = String::new();
+    let empty = Vec::new();
+    let rows = next.rows.len().max(previous.rows.len());
+
+    for y in 0..rows {
+        let old = previous.rows.get(y).unwrap_or(&empty);
+        let new = next.rows.get(y).unwrap_or(&empty);
+
+        let mut column = 0;
+        let mut cursor_in_place = false;
+        let mut shifted = false;
+
+        for (x, cell) in new.iter().enumerate() {
+            let old_cell = old.get(x);
+
+            // Once a cell changes width, everything after it moves.
+            shifted |= old_cell.is_none_or(|c| c.width != cell.width);
+
+            if shifted || old_cell != Some(cell) {
+                if !cursor_in_place {
+                    out.push_str(&format!("\x1b[{};{}H", y + 1, column + 1));
+                }
+                if cell.highlight {
+                    out.push_str(&format!("\x1b[7m{}\x1b[27m", cell.text));
+                } else {
+                    out.push_str(&cell.text);
+                }
+                cursor_in_place = true;
+            } else {
+                cursor_in_place = false;
+            }
+
+            column += cell.width;
+        }
+
+        let old_width: usize = old.iter().map(|c| c.width).sum();
+        if old_width > column {
+            out.push_str(&format!("\x1b[{};{}H\x1b[K", y + 1, column + 1));
+        }
+    }
+
+    out
+}
@@ -0,0 +1,113 @@
+//! Checking LURD solutions by playing them back on a level.
+
+use crate::{Board, Direction, Game, GameStatus};
+use std::fmt;
+
+#[must_use]
+#[derive(Debug, PartialEq)]
+pub enum ReplayError {
+    UnknownChar { position: usize, c: char },
+    IllegalMove { position: usize, c: char },
+    PushMismatch { position: usize, c: char },
+    MovesAfterSolved { position: usize },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::UnknownChar { position, c } => {
+                write!(f, "unknown move {:?} at position {}", c, position)
+            }
+            ReplayError::IllegalMove { position, c } => {
+                write!(f, "illegal move {:?} at position {}", c, position)
+            }
+            ReplayError::PushMismatch { position, c } => write!(
+                f,
+                "move {:?} at position {} has the wrong case for whether it pushes",
+                c, position
+            ),
+            ReplayError::MovesAfterSolved { position } => {
+                write!(f, "level already solved before position {}", position)
+            }
+        }
+    }
+}
+
+#[must_use]
+pub struct ReplayReport {
+    pub error: Option<ReplayError>,
+    pub solved: bool,
+    pub moves: usize,
+    pub pushes: usize,
+    /// The position reached after the last valid move.
+    pub board: Board,
+}
+
+impl ReplayReport {
+    #[must_use]
+    pub fn valid(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl fmt::Display for ReplayReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+
+        match &self.error {
+            Some(error) => writeln!(f, "valid:  no ({})", error)?,
+            None => writeln!(f, "valid:  yes")?,
+        }
+        writeln!(f, "solved: {}", yes_no(self.solved))?;
+        writeln!(f, "moves:  {}", self.moves)?;
+        write!(f, "pushes: {}", self.pushes)
+    }
+}
+
+/// Plays `lurd` move by move on a fresh game of `board`, stopping at the
+/// first invalid move. Whitespace in the solution is ignored. Positions
+/// in errors are 1-based and count only moves.
+///
+/// # Panics
+///
+/// If `board` has no player, like [`Game::new`].
+pub fn replay(board: &Board, lurd: &str) -> ReplayReport {
+    let mut game = Game::new(board.clone());
+
+    let error = lurd
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .enumerate()
+        .map(|(i, c)| (i + 1, c))
+        .find_map(|(position, c)| {
+            let (direction, push) = match Direction::from_lurd(c) {
+                Some(parsed) => parsed,
+                None => return Some(ReplayError::UnknownChar { position, c }),
+            };
+
+            if game.status() == GameStatus::Won {
+                return Some(ReplayError::MovesAfterSolved { position });
+            }
+
+            let pushes = game.pushes();
+            if !game.move_player(direction) {
+                return Some(ReplayError::IllegalMove { position, c });
+            }
+
+            if (game.pushes() != pushes) != push {
+                // Leave the board as it was before the rejected move.
+                game.undo();
+                return Some(ReplayError::PushMismatch { position, c });
+            }
+
+            None
+        });
+
+    ReplayReport {
+        error,
+        solved: game.status() == GameStatus::Won,
+        moves: game.moves(),
+        pushes: game.pushes(),
+        board: game.board().clone(),
+    }
+}
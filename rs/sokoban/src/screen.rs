@@ -0,0 +1,76 @@
+//! Drawing frames to the terminal.
+//!
+//! On a terminal, the game is drawn on the alternate screen using ANSI
+//! escape sequences, and each frame only rewrites the cells that changed
+//! since the previous one. When stdout is not a terminal, every frame is
+//! printed in full as plain text.
+
+use sokoban::render::{diff, Frame};
+use sokoban::theme::{ColorMode, Theme};
+use std::io::{self, IsTerminal, Write};
+
+const ENTER_SCREEN: &str = "\x1b[?1049h\x1b[?25l\x1b[H\x1b[2J";
+const LEAVE_SCREEN: &str = "\x1b[?25h\x1b[?1049l";
+
+#[must_use]
+pub struct Renderer {
+    ansi: bool,
+    theme: Theme,
+    previous: Frame,
+}
+
+impl Renderer {
+    /// Takes over the terminal if stdout is one, restoring it when the
+    /// renderer is dropped or the program panics. Colors are only used on
+    /// a terminal.
+    pub fn new(mut theme: Theme) -> Renderer {
+        let ansi = io::stdout().is_terminal();
+        if !ansi {
+            theme.colors = ColorMode::None;
+        }
+
+        if ansi {
+            print!("{}", ENTER_SCREEN);
+
+            let previous = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                print!("{}", LEAVE_SCREEN);
+                let _ = io::stdout().flush();
+                previous(info);
+            }));
+        }
+
+        Renderer {
+            ansi,
+            theme,
+            previous: Frame::new(),
+        }
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn draw(&mut self, frame: Frame) {
+        let out = if self.ansi {
+            diff(&self.previous, &frame)
+        } else {
+            frame.to_plain_string() + "\n"
+        };
+
+        let mut stdout = io::stdout().lock();
+        let _ = stdout.write_all(out.as_bytes());
+        let _ = stdout.flush();
+
+        self.previous = frame;
+    }
+}
+
+impl Drop for Renderer {
+    fn drop(&mut self) {
+        if self.ansi {
+            print!("{}", LEAVE_SCREEN);
+            let _ = io::stdout().flush();
+        }
+    }
+}
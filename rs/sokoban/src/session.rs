@@ -0,0 +1,144 @@
+//! Games in progress, saved to `$XDG_DATA_HOME/sokoban/session` so they can
+//! be resumed on the next launch.
+//!
+//! The file holds `key = value` lines, then a blank line and the level as
+//! it was before the first move, in XSB:
+//!
+//! ```text
+//! pack = /home/me/levels.txt
+//! level = 3
+//! elapsed = 95
+//! hints = 1
+//! lurd = rrdLL
+//!
+//! #####
+//! #@$.#
+//! #####
+//! ```
+
+use crate::{config, xsb};
+use crate::{Board, Direction, Game};
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[must_use]
+pub struct Session {
+    /// The level pack file, or `None` for the built-in level.
+    pub pack: Option<String>,
+    /// Index of the level within the pack.
+    pub level: usize,
+    /// The level before any move was made.
+    pub board: Board,
+    pub lurd: String,
+    pub elapsed: Duration,
+    pub hints: usize,
+}
+
+impl Session {
+    /// Captures `game`, which was started from `board`.
+    pub fn new(pack: Option<String>, level: usize, board: Board, game: &Game) -> Session {
+        Session {
+            pack,
+            level,
+            board,
+            lurd: game.lurd(),
+            elapsed: game.elapsed(),
+            hints: game.hints(),
+        }
+    }
+
+    #[must_use]
+    pub fn path() -> Option<PathBuf> {
+        config::data_dir().map(|dir| dir.join("session"))
+    }
+
+    /// Reads the saved session, if there is one. Unreadable or malformed
+    /// files are ignored.
+    #[must_use]
+    pub fn load() -> Option<Session> {
+        let text = std::fs::read_to_string(Session::path()?).ok()?;
+        Session::parse(&text)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = Session::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_text())
+    }
+
+    /// Deletes the saved session, if any.
+    pub fn remove() {
+        if let Some(path) = Session::path() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        if let Some(pack) = &self.pack {
+            out.push_str(&format!("pack = {}\n", pack));
+        }
+        out.push_str(&format!("level = {}\n", self.level));
+        out.push_str(&format!("elapsed = {}\n", self.elapsed.as_secs()));
+        out.push_str(&format!("hints = {}\n", self.hints));
+        out.push_str(&format!("lurd = {}\n", self.lurd));
+        out.push('\n');
+        out.push_str(&xsb::to_xsb(&self.board));
+        out
+    }
+
+    #[must_use]
+    pub fn parse(text: &str) -> Option<Session> {
+        let (header, level) = text.split_once("\n\n")?;
+
+        let (mut pack, mut number, mut elapsed, mut lurd) = (None, None, None, None);
+        // Sessions saved before hints existed have none.
+        let mut hints = 0;
+        for line in header.lines() {
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+
+            match key.trim() {
+                "pack" => pack = Some(value.to_string()),
+                "level" => number = Some(value.parse().ok()?),
+                "elapsed" => elapsed = Some(Duration::from_secs(value.parse().ok()?)),
+                "hints" => hints = value.parse().ok()?,
+                "lurd" => lurd = Some(value.to_string()),
+                _ => return None,
+            }
+        }
+
+        Some(Session {
+            pack,
+            level: number?,
+            board: xsb::parse_board(level).ok()?,
+            lurd: lurd?,
+            elapsed: elapsed?,
+            hints,
+        })
+    }
+
+    /// Rebuilds the game by replaying the saved moves, or returns `None` if
+    /// they don't apply to the saved level.
+    #[must_use]
+    pub fn to_game(&self) -> Option<Game> {
+        let mut game = Game::new(self.board.clone());
+
+        for c in self.lurd.chars() {
+            let (direction, _) = Direction::from_lurd(c)?;
+            if !game.move_player(direction) {
+                return None;
+            }
+        }
+
+        game.set_elapsed(self.elapsed);
+        game.set_hints(self.hints);
+        Some(game)
+    }
+}
@@ -0,0 +1,370 @@
+//! Automatic solving of levels.
+//!
+//! The search runs A* over box configurations, treating each push as a step
+//! of cost one. Player positions are normalised to the top-left-most cell
+//! of the region the player can reach, so that positions differing only by
+//! player walks share an entry in the transposition table. The heuristic
+//! greedily matches boxes to goals using precomputed push distances, which
+//! keeps it cheap but means solutions are not guaranteed to be push-optimal.
+
+use crate::deadlock;
+use crate::{Board, Direction, Index, Obj, Tile};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+const UNREACHABLE: usize = usize::MAX;
+const NO_PARENT: usize = usize::MAX;
+
+#[must_use]
+#[derive(Copy, Clone)]
+pub struct Limits {
+    pub max_nodes: usize,
+    pub max_time: Duration,
+}
+
+impl Default for Limits {
+    fn default() -> Limits {
+        Limits {
+            max_nodes: 2_000_000,
+            max_time: Duration::from_secs(30),
+        }
+    }
+}
+
+#[must_use]
+#[derive(Debug, PartialEq)]
+pub enum SolveError {
+    Unsolvable,
+    NodeLimit,
+    TimeLimit,
+    MissingPlayer,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::Unsolvable => write!(f, "level has no solution"),
+            SolveError::NodeLimit => write!(f, "node limit reached"),
+            SolveError::TimeLimit => write!(f, "time limit reached"),
+            SolveError::MissingPlayer => write!(f, "level has no player"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+#[must_use]
+pub struct Solution {
+    pub lurd: String,
+    pub pushes: usize,
+    pub nodes: usize,
+}
+
+#[must_use]
+struct Node {
+    boxes: Vec<Index>,
+    player: Index,
+    parent: usize,
+    push: (Index, Direction),
+}
+
+#[must_use]
+struct Search<'a> {
+    board: &'a Board,
+    goals: Vec<Index>,
+    /// `distances[g][i]` is the number of pushes needed to bring a box
+    /// from cell `i` to the `g`-th goal, ignoring all other boxes.
+    distances: Vec<Vec<usize>>,
+    /// Cells from which a box can never reach any goal.
+    dead: Vec<bool>,
+}
+
+impl<'a> Search<'a> {
+    fn new(board: &'a Board) -> Search<'a> {
+        let goals: Vec<Index> = (0..board.tiles.len())
+            .filter(|&i| board.tiles[i] == Tile::Goal)
+            .collect();
+
+        let mut search = Search {
+            board,
+            goals,
+            distances: Vec::new(),
+            dead: Vec::new(),
+        };
+
+        search.distances = search
+            .goals
+            .iter()
+            .map(|&goal| search.pull_distances(goal))
+            .collect();
+        search.dead = deadlock::dead_squares(board);
+
+        search
+    }
+
+    #[must_use]
+    fn is_floor(&self, i: Index) -> bool {
+        self.board.tiles[i] != Tile::Wall
+    }
+
+    /// Computes push distances to `goal` by pulling a box away from it.
+    #[must_use]
+    fn pull_distances(&self, goal: Index) -> Vec<usize> {
+        let mut distances = vec![UNREACHABLE; self.board.tiles.len()];
+        let mut queue = VecDeque::new();
+
+        distances[goal] = 0;
+        queue.push_back(goal);
+
+        while let Some(target) = queue.pop_front() {
+            for direction in Direction::iter() {
+                let source = match self.board.neighbour(target, direction.opposite()) {
+                    Some(source) if self.is_floor(source) => source,
+                    _ => continue,
+                };
+
+                match self.board.neighbour(source, direction.opposite()) {
+                    Some(pusher) if self.is_floor(pusher) => {}
+                    _ => continue,
+                }
+
+                if distances[source] == UNREACHABLE {
+                    distances[source] = distances[target] + 1;
+                    queue.push_back(source);
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// Estimates the pushes left by greedily matching boxes to goals,
+    /// returning `None` if some box can no longer reach any goal.
+    #[must_use]
+    fn heuristic(&self, boxes: &[Index]) -> Option<usize> {
+        let mut pairs = Vec::with_capacity(boxes.len() * self.goals.len());
+        for (b, &i) in boxes.iter().enumerate() {
+            for (g, distances) in self.distances.iter().enumerate() {
+                if distances[i] != UNREACHABLE {
+                    pairs.push((distances[i], b, g));
+                }
+            }
+        }
+        pairs.sort_unstable();
+
+        let mut box_matched = vec![false; boxes.len()];
+        let mut goal_matched = vec![false; self.goals.len()];
+        let mut total = 0;
+
+        for &(distance, b, g) in &pairs {
+            if !box_matched[b] && !goal_matched[g] {
+                box_matched[b] = true;
+                goal_matched[g] = true;
+                total += distance;
+            }
+        }
+
+        // Boxes left over by the greedy matching still need at least as
+        // many pushes as it takes them to reach their nearest goal.
+        for (b, &i) in boxes.iter().enumerate() {
+            if !box_matched[b] {
+                let nearest = self.distances.iter().map(|d| d[i]).min()?;
+                if nearest == UNREACHABLE {
+                    return None;
+                }
+                total += nearest;
+            }
+        }
+
+        Some(total)
+    }
+
+    #[must_use]
+    fn occupancy(&self, boxes: &[Index]) -> Vec<bool> {
+        let mut occupied = vec![false; self.board.tiles.len()];
+        for &b in boxes {
+            occupied[b] = true;
+        }
+        occupied
+    }
+
+    /// Flood fills the cells the player can walk to from `from`.
+    #[must_use]
+    fn reachable(&self, occupied: &[bool], from: Index) -> Vec<bool> {
+        self.board
+            .flood_fill(from, |n| self.is_floor(n) && !occupied[n])
+    }
+
+    /// Finds the shortest walk from `from` to `to`.
+    #[must_use]
+    fn walk(&self, occupied: &[bool], from: Index, to: Index) -> Option<Vec<Direction>> {
+        self.board
+            .shortest_path(from, to, |n| self.is_floor(n) && !occupied[n])
+    }
+
+    #[must_use]
+    fn is_solved(&self, boxes: &[Index]) -> bool {
+        boxes.iter().all(|&b| self.board.tiles[b] == Tile::Goal)
+    }
+
+    /// Expands the chain of pushes ending at `nodes[last]` into LURD,
+    /// walking the player between pushes.
+    #[must_use]
+    fn to_lurd(&self, nodes: &[Node], last: usize, player: Index) -> String {
+        let mut pushes = Vec::new();
+        let mut id = last;
+        while nodes[id].parent != NO_PARENT {
+            pushes.push(nodes[id].push);
+            id = nodes[id].parent;
+        }
+        pushes.reverse();
+
+        let mut occupied = self.occupancy(&nodes[id].boxes);
+        let mut player = player;
+        let mut lurd = String::new();
+
+        for (source, direction) in pushes {
+            let pusher = self.board.neighbour(source, direction.opposite()).unwrap();
+            let target = self.board.neighbour(source, direction).unwrap();
+
+            for step in self.walk(&occupied, player, pusher).unwrap() {
+                lurd.push(step.to_lurd(false));
+            }
+            lurd.push(direction.to_lurd(true));
+
+            occupied[source] = false;
+            occupied[target] = true;
+            player = source;
+        }
+
+        lurd
+    }
+}
+
+/// Searches for a solution of `board` from its current position, so it
+/// can be used on games in progress as well as on fresh levels.
+pub fn solve(board: &Board, limits: &Limits) -> Result<Solution, SolveError> {
+    let started = Instant::now();
+    let search = Search::new(board);
+
+    let player = board.find_player().ok_or(SolveError::MissingPlayer)?;
+    let boxes: Vec<Index> = (0..board.objects.len())
+        .filter(|&i| board.objects[i] == Obj::Box)
+        .collect();
+
+    let start_heuristic = search.heuristic(&boxes).ok_or(SolveError::Unsolvable)?;
+    let occupied = search.occupancy(&boxes);
+    let normalized = search
+        .reachable(&occupied, player)
+        .iter()
+        .position(|&r| r)
+        .unwrap();
+
+    let mut nodes = vec![Node {
+        boxes: boxes.clone(),
+        player: normalized,
+        parent: NO_PARENT,
+        push: (0, Direction::Up),
+    }];
+    let mut seen: HashMap<(Vec<Index>, Index), usize> = HashMap::new();
+    // Ties on the estimated total are broken in favour of deeper nodes.
+    let mut open = BinaryHeap::new();
+
+    seen.insert((boxes, normalized), 0);
+    open.push(Reverse((start_heuristic, Reverse(0), 0)));
+
+    let mut expanded = 0;
+
+    while let Some(Reverse((_, Reverse(cost), id))) = open.pop() {
+        let key = (nodes[id].boxes.clone(), nodes[id].player);
+        if seen.get(&key).is_some_and(|&best| best < cost) {
+            continue;
+        }
+
+        if search.is_solved(&nodes[id].boxes) {
+            return Ok(Solution {
+                lurd: search.to_lurd(&nodes, id, player),
+                pushes: cost,
+                nodes: expanded,
+            });
+        }
+
+        expanded += 1;
+        if expanded > limits.max_nodes {
+            return Err(SolveError::NodeLimit);
+        }
+        if expanded % 1024 == 0 && started.elapsed() > limits.max_time {
+            return Err(SolveError::TimeLimit);
+        }
+
+        let occupied = search.occupancy(&nodes[id].boxes);
+        let reached = search.reachable(&occupied, nodes[id].player);
+
+        for k in 0..nodes[id].boxes.len() {
+            let source = nodes[id].boxes[k];
+
+            for direction in Direction::iter() {
+                let (pusher, target) = match (
+                    board.neighbour(source, direction.opposite()),
+                    board.neighbour(source, direction),
+                ) {
+                    (Some(pusher), Some(target)) => (pusher, target),
+                    _ => continue,
+                };
+
+                if !reached[pusher]
+                    || !search.is_floor(target)
+                    || occupied[target]
+                    || search.dead[target]
+                {
+                    continue;
+                }
+
+                let mut boxes = nodes[id].boxes.clone();
+                boxes[k] = target;
+
+                let heuristic = match search.heuristic(&boxes) {
+                    Some(h) => h,
+                    None => continue,
+                };
+
+                let mut next_occupied = occupied.clone();
+                next_occupied[source] = false;
+                next_occupied[target] = true;
+
+                if deadlock::is_freeze_deadlock(board, &search.dead, &next_occupied, target) {
+                    continue;
+                }
+                let player = search
+                    .reachable(&next_occupied, source)
+                    .iter()
+                    .position(|&r| r)
+                    .unwrap();
+
+                boxes.sort_unstable();
+                let key = (boxes, player);
+                if seen.get(&key).is_some_and(|&best| best <= cost + 1) {
+                    continue;
+                }
+
+                let (boxes, player) = key.clone();
+                seen.insert(key, cost + 1);
+                nodes.push(Node {
+                    boxes,
+                    player,
+                    parent: id,
+                    push: (source, direction),
+                });
+                open.push(Reverse((
+                    cost + 1 + heuristic,
+                    Reverse(cost + 1),
+                    nodes.len() - 1,
+                )));
+            }
+        }
+    }
+
+    Err(SolveError::Unsolvable)
+}
@@ -0,0 +1,218 @@
+//! Unbuffered keyboard input.
+//!
+//! When stdin is a terminal, it is switched to raw mode so that keys are
+//! delivered as soon as they are pressed, and arrow-key escape sequences are
+//! decoded. Otherwise, input is read byte by byte as-is.
+
+use std::io::{self, Read};
+
+#[must_use]
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Key {
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Escape,
+    Backspace,
+    Interrupt,
+    Eof,
+    /// An escape sequence for a key the game has no use for, such as
+    /// Delete or a function key.
+    Unknown,
+}
+
+#[cfg(unix)]
+mod raw {
+    use std::sync::Mutex;
+
+    static ORIGINAL: Mutex<Option<libc::termios>> = Mutex::new(None);
+
+    pub fn enable() -> bool {
+        // SAFETY: `termios` is plain data, and is only used after
+        // `tcgetattr` has filled it in.
+        unsafe {
+            if libc::isatty(libc::STDIN_FILENO) == 0 {
+                return false;
+            }
+
+            let mut original: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut original) != 0 {
+                return false;
+            }
+
+            let mut raw = original;
+            raw.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG | libc::IEXTEN);
+            raw.c_iflag &= !(libc::IXON | libc::ICRNL);
+            raw.c_cc[libc::VMIN] = 1;
+            raw.c_cc[libc::VTIME] = 0;
+
+            if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &raw) != 0 {
+                return false;
+            }
+
+            *ORIGINAL.lock().unwrap_or_else(|e| e.into_inner()) = Some(original);
+        }
+
+        // Restore the terminal before the panic message is printed, so it
+        // isn't mangled and the shell is usable afterwards.
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            disable();
+            previous(info);
+        }));
+
+        true
+    }
+
+    pub fn disable() {
+        let original = ORIGINAL.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(original) = original {
+            // SAFETY: `original` was obtained from `tcgetattr`.
+            unsafe {
+                libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &original);
+            }
+        }
+    }
+
+    pub fn is_enabled() -> bool {
+        ORIGINAL.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+    }
+
+    #[must_use]
+    pub fn read_byte() -> Option<u8> {
+        let mut byte = 0u8;
+        // SAFETY: reads at most one byte into `byte`.
+        let n = unsafe { libc::read(libc::STDIN_FILENO, (&mut byte as *mut u8).cast(), 1) };
+        if n == 1 {
+            Some(byte)
+        } else {
+            None
+        }
+    }
+
+    /// Waits briefly for another byte, to tell a lone Escape key apart from
+    /// the start of an escape sequence.
+    #[must_use]
+    pub fn read_byte_soon() -> Option<u8> {
+        let mut fd = libc::pollfd {
+            fd: libc::STDIN_FILENO,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        // SAFETY: `fd` is a single valid `pollfd`.
+        if unsafe { libc::poll(&mut fd, 1, 50) } == 1 {
+            read_byte()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod raw {
+    pub fn enable() -> bool {
+        false
+    }
+
+    pub fn disable() {}
+
+    pub fn is_enabled() -> bool {
+        false
+    }
+
+    #[must_use]
+    pub fn read_byte() -> Option<u8> {
+        None
+    }
+
+    #[must_use]
+    pub fn read_byte_soon() -> Option<u8> {
+        None
+    }
+}
+
+/// Keeps the terminal in raw mode for as long as it is alive.
+#[must_use]
+pub struct RawMode {
+    enabled: bool,
+}
+
+impl RawMode {
+    /// Switches the terminal to raw mode. Does nothing if stdin is not a
+    /// terminal.
+    pub fn enable() -> RawMode {
+        RawMode {
+            enabled: raw::enable(),
+        }
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        if self.enabled {
+            raw::disable();
+        }
+    }
+}
+
+#[must_use]
+fn read_byte() -> Option<u8> {
+    if raw::is_enabled() {
+        raw::read_byte()
+    } else {
+        io::stdin().lock().bytes().next().and_then(|b| b.ok())
+    }
+}
+
+/// Blocks until a key is pressed.
+pub fn read_key() -> Key {
+    let byte = match read_byte() {
+        Some(byte) => byte,
+        None => return Key::Eof,
+    };
+
+    match byte {
+        b'\r' | b'\n' => Key::Enter,
+        0x03 => Key::Interrupt,
+        0x04 => Key::Eof,
+        0x08 | 0x7f => Key::Backspace,
+        0x1b if raw::is_enabled() => read_escape_sequence(),
+        0x1b => Key::Escape,
+        _ => Key::Char(byte as char),
+    }
+}
+
+/// Reads the rest of an escape sequence up to its final byte, so that no
+/// part of it is mistaken for a separate key press.
+fn read_escape_sequence() -> Key {
+    let csi = match raw::read_byte_soon() {
+        Some(b'[') => true,
+        Some(b'O') => false,
+        _ => return Key::Escape,
+    };
+
+    loop {
+        let byte = match raw::read_byte_soon() {
+            Some(byte) => byte,
+            None => return Key::Unknown,
+        };
+
+        // Control sequences have parameters before the final byte, while
+        // `ESC O` is followed by the final byte alone.
+        if csi && !(0x40..=0x7e).contains(&byte) {
+            continue;
+        }
+
+        return match byte {
+            b'A' => Key::Up,
+            b'B' => Key::Down,
+            b'C' => Key::Right,
+            b'D' => Key::Left,
+            _ => Key::Unknown,
+        };
+    }
+}
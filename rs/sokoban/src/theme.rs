@@ -0,0 +1,198 @@
+//! Glyph sets and colors used to draw boards.
+
+use crate::render::Cell;
+use crate::{Obj, Tile};
+
+#[must_use]
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum ColorMode {
+    None,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorMode {
+    #[must_use]
+    pub fn parse(name: &str) -> Option<ColorMode> {
+        match name {
+            "none" | "off" => Some(ColorMode::None),
+            "256" => Some(ColorMode::Ansi256),
+            "truecolor" | "24bit" => Some(ColorMode::TrueColor),
+            _ => None,
+        }
+    }
+
+    /// Guesses what the terminal supports from `COLORTERM` and `TERM`.
+    pub fn detect() -> ColorMode {
+        let var = |name| std::env::var(name).unwrap_or_default();
+
+        match (var("COLORTERM").as_str(), var("TERM")) {
+            ("truecolor", _) | ("24bit", _) => ColorMode::TrueColor,
+            (_, term) if term.contains("256color") => ColorMode::Ansi256,
+            _ => ColorMode::None,
+        }
+    }
+}
+
+#[must_use]
+#[derive(Copy, Clone)]
+pub struct Color {
+    pub ansi256: u8,
+    pub rgb: (u8, u8, u8),
+}
+
+const GREY: Color = Color {
+    ansi256: 244,
+    rgb: (128, 128, 128),
+};
+const YELLOW: Color = Color {
+    ansi256: 220,
+    rgb: (255, 208, 0),
+};
+const CYAN: Color = Color {
+    ansi256: 51,
+    rgb: (0, 255, 255),
+};
+const BROWN: Color = Color {
+    ansi256: 130,
+    rgb: (175, 95, 0),
+};
+const GREEN: Color = Color {
+    ansi256: 46,
+    rgb: (0, 255, 0),
+};
+
+#[must_use]
+#[derive(Copy, Clone)]
+pub struct Glyph {
+    pub text: &'static str,
+    pub color: Option<Color>,
+}
+
+const fn glyph(text: &'static str, color: Color) -> Glyph {
+    Glyph {
+        text,
+        color: Some(color),
+    }
+}
+
+const fn plain(text: &'static str) -> Glyph {
+    Glyph { text, color: None }
+}
+
+#[must_use]
+#[derive(Copy, Clone)]
+pub struct Theme {
+    pub name: &'static str,
+    /// Number of terminal columns taken by every glyph.
+    pub width: usize,
+    pub floor: Glyph,
+    pub wall: Glyph,
+    pub goal: Glyph,
+    pub player: Glyph,
+    pub player_on_goal: Glyph,
+    pub box_on_floor: Glyph,
+    pub box_on_goal: Glyph,
+    pub colors: ColorMode,
+}
+
+pub static THEMES: [Theme; 4] = [
+    Theme {
+        name: "unicode",
+        width: 1,
+        floor: plain(" "),
+        wall: glyph("▒", GREY),
+        goal: glyph("○", YELLOW),
+        player: glyph("☻", CYAN),
+        player_on_goal: glyph("☺", CYAN),
+        box_on_floor: glyph("■", BROWN),
+        box_on_goal: glyph("◙", GREEN),
+        colors: ColorMode::None,
+    },
+    Theme {
+        name: "ascii",
+        width: 1,
+        floor: plain(" "),
+        wall: glyph("#", GREY),
+        goal: glyph(".", YELLOW),
+        player: glyph("@", CYAN),
+        player_on_goal: glyph("+", CYAN),
+        box_on_floor: glyph("$", BROWN),
+        box_on_goal: glyph("*", GREEN),
+        colors: ColorMode::None,
+    },
+    Theme {
+        name: "emoji",
+        width: 2,
+        floor: plain("  "),
+        wall: plain("🧱"),
+        goal: plain("🎯"),
+        player: plain("😀"),
+        player_on_goal: plain("😎"),
+        box_on_floor: plain("📦"),
+        box_on_goal: plain("✅"),
+        colors: ColorMode::None,
+    },
+    Theme {
+        name: "wide",
+        width: 2,
+        floor: plain("  "),
+        wall: glyph("██", GREY),
+        goal: glyph("<>", YELLOW),
+        player: glyph("()", CYAN),
+        player_on_goal: glyph("<)", CYAN),
+        box_on_floor: glyph("[]", BROWN),
+        box_on_goal: glyph("{}", GREEN),
+        colors: ColorMode::None,
+    },
+];
+
+impl Theme {
+    pub fn by_name(name: &str) -> Option<Theme> {
+        THEMES.iter().find(|t| t.name == name).copied()
+    }
+
+    #[must_use]
+    pub fn names() -> Vec<&'static str> {
+        THEMES.iter().map(|t| t.name).collect()
+    }
+
+    fn glyph(&self, obj: Obj, tile: Tile) -> Glyph {
+        match (obj, tile) {
+            (Obj::Player, Tile::Goal) => self.player_on_goal,
+            (Obj::Player, _) => self.player,
+            (Obj::Box, Tile::Goal) => self.box_on_goal,
+            (Obj::Box, _) => self.box_on_floor,
+            (Obj::None, Tile::None) => self.floor,
+            (Obj::None, Tile::Wall) => self.wall,
+            (Obj::None, Tile::Goal) => self.goal,
+        }
+    }
+
+    pub fn cell(&self, obj: Obj, tile: Tile) -> Cell {
+        let glyph = self.glyph(obj, tile);
+
+        let text = match (glyph.color, self.colors) {
+            (Some(c), ColorMode::Ansi256) => {
+                format!("\x1b[38;5;{}m{}\x1b[0m", c.ansi256, glyph.text)
+            }
+            (Some(c), ColorMode::TrueColor) => {
+                let (r, g, b) = c.rgb;
+                format!("\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, glyph.text)
+            }
+            _ => glyph.text.to_string(),
+        };
+
+        Cell {
+            text,
+            width: self.width,
+            highlight: false,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        THEMES[0]
+    }
+}
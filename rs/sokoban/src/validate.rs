@@ -0,0 +1,225 @@
+//! Structural checks on levels, for boards that don't come from the XSB
+//! parser such as those made in the editor or submitted by players.
+//!
+//! Problems are errors when the level can't be played or solved, and
+//! warnings otherwise. They can be written as JSON for other tools:
+//!
+//! ```text
+//! {"level": 1, "title": "Intro", "valid": false, "problems": [
+//!   {"code": "dead_box", "severity": "error", "x": 3, "y": 1,
+//!    "message": "box at (3, 1) can never be pushed onto a goal"}]}
+//! ```
+
+use crate::deadlock;
+use crate::xsb::{self, ParseError};
+use crate::{Board, Obj, Tile, Vec2D};
+use std::fmt;
+
+#[must_use]
+#[derive(Debug, PartialEq)]
+pub enum Problem {
+    Empty,
+    UnknownGlyph { at: Vec2D, glyph: char },
+    MissingPlayer,
+    MultiplePlayers { count: usize },
+    BoxGoalMismatch { boxes: usize, goals: usize },
+    AlreadySolved,
+    UnreachableBox { at: Vec2D },
+    UnreachableGoal { at: Vec2D },
+    DeadBox { at: Vec2D },
+    OpenBorder { at: Vec2D },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::Empty => write!(f, "level is empty"),
+            Problem::UnknownGlyph { at, glyph } => {
+                write!(f, "unknown glyph {:?} at {:?}", glyph, at)
+            }
+            Problem::MissingPlayer => write!(f, "level has no player"),
+            Problem::MultiplePlayers { count } => write!(f, "level has {} players", count),
+            Problem::BoxGoalMismatch { boxes, goals } => {
+                write!(f, "level has {} boxes but {} goals", boxes, goals)
+            }
+            Problem::AlreadySolved => write!(f, "level is solved before any move"),
+            Problem::UnreachableBox { at } => {
+                write!(f, "box at {:?} can't be reached by the player", at)
+            }
+            Problem::UnreachableGoal { at } => {
+                write!(f, "goal at {:?} can't be reached by the player", at)
+            }
+            Problem::DeadBox { at } => {
+                write!(f, "box at {:?} can never be pushed onto a goal", at)
+            }
+            Problem::OpenBorder { at } => {
+                write!(f, "player can walk off the board at {:?}", at)
+            }
+        }
+    }
+}
+
+impl Problem {
+    /// A stable identifier for the kind of problem.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Problem::Empty => "empty",
+            Problem::UnknownGlyph { .. } => "unknown_glyph",
+            Problem::MissingPlayer => "missing_player",
+            Problem::MultiplePlayers { .. } => "multiple_players",
+            Problem::BoxGoalMismatch { .. } => "box_goal_mismatch",
+            Problem::AlreadySolved => "already_solved",
+            Problem::UnreachableBox { .. } => "unreachable_box",
+            Problem::UnreachableGoal { .. } => "unreachable_goal",
+            Problem::DeadBox { .. } => "dead_box",
+            Problem::OpenBorder { .. } => "open_border",
+        }
+    }
+
+    /// Whether the problem keeps the level from being played. Open borders
+    /// only matter to programs that don't treat the edge as a wall.
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Problem::OpenBorder { .. })
+    }
+
+    #[must_use]
+    pub fn position(&self) -> Option<Vec2D> {
+        match self {
+            Problem::UnknownGlyph { at, .. }
+            | Problem::UnreachableBox { at }
+            | Problem::UnreachableGoal { at }
+            | Problem::DeadBox { at }
+            | Problem::OpenBorder { at } => Some(*at),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let mut fields = vec![
+            format!("\"code\": {}", json_string(self.code())),
+            format!(
+                "\"severity\": \"{}\"",
+                if self.is_error() { "error" } else { "warning" }
+            ),
+        ];
+
+        if let Some((x, y)) = self.position() {
+            fields.push(format!("\"x\": {}, \"y\": {}", x, y));
+        }
+        fields.push(format!("\"message\": {}", json_string(&self.to_string())));
+
+        format!("{{{}}}", fields.join(", "))
+    }
+}
+
+/// Quotes `s` as a JSON string.
+#[must_use]
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Writes the problems found in level `number` of a pack as a JSON
+/// object. The level is valid if none of them are errors.
+#[must_use]
+pub fn level_json(number: usize, title: &str, problems: &[Problem]) -> String {
+    let json: Vec<String> = problems.iter().map(|p| p.to_json()).collect();
+
+    format!(
+        "{{\"level\": {}, \"title\": {}, \"valid\": {}, \"problems\": [{}]}}",
+        number,
+        json_string(title),
+        is_valid(problems),
+        json.join(", ")
+    )
+}
+
+/// Lists everything wrong with `board`, with problems concerning single
+/// cells in reading order. Boxes and goals count as reachable if the
+/// player can walk to them with the boxes out of the way.
+pub fn validate(board: &Board) -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    let players: Vec<usize> = (0..board.objects.len())
+        .filter(|&i| board.objects[i] == Obj::Player)
+        .collect();
+
+    match players.len() {
+        0 => problems.push(Problem::MissingPlayer),
+        1 => {}
+        count => problems.push(Problem::MultiplePlayers { count }),
+    }
+
+    let (boxes, goals) = (board.count_boxes(), board.count_goals());
+    if boxes != goals {
+        problems.push(Problem::BoxGoalMismatch { boxes, goals });
+    } else if board.count_goals_left() == 0 {
+        problems.push(Problem::AlreadySolved);
+    }
+
+    let reached = match players.first() {
+        Some(&player) => board.flood_fill(player, |n| board.tiles[n] != Tile::Wall),
+        None => vec![true; board.tiles.len()],
+    };
+    let dead = deadlock::dead_squares(board);
+
+    for i in 0..board.tiles.len() {
+        let at = board.to_vec2d(i);
+        let is_box = board.objects[i] == Obj::Box;
+        let (x, y) = at;
+
+        if !reached[i] && is_box {
+            problems.push(Problem::UnreachableBox { at });
+        } else if !reached[i] && board.tiles[i] == Tile::Goal {
+            problems.push(Problem::UnreachableGoal { at });
+        }
+
+        if is_box && dead[i] && board.tiles[i] != Tile::Goal {
+            problems.push(Problem::DeadBox { at });
+        }
+
+        let on_edge = x == 0 || y == 0 || x + 1 == board.width || y + 1 == board.height;
+        if on_edge && reached[i] && !players.is_empty() && board.tiles[i] != Tile::Wall {
+            problems.push(Problem::OpenBorder { at });
+        }
+    }
+
+    problems
+}
+
+/// Lists everything wrong with the XSB level `text`, which includes not
+/// being readable at all.
+pub fn validate_text(text: &str) -> Vec<Problem> {
+    match xsb::parse_layout(text) {
+        Ok(board) => validate(&board),
+        Err(ParseError::UnknownGlyph {
+            line,
+            column,
+            glyph,
+        }) => {
+            let first = text.lines().position(|l| !l.trim_end().is_empty());
+            let at = (column - 1, line - 1 - first.unwrap_or(0));
+            vec![Problem::UnknownGlyph { at, glyph }]
+        }
+        Err(_) => vec![Problem::Empty],
+    }
+}
+
+/// Whether a level with these problems can be played.
+#[must_use]
+pub fn is_valid(problems: &[Problem]) -> bool {
+    !problems.iter().any(Problem::is_error)
+}
@@ -0,0 +1,176 @@
+//! Parsing and writing of levels in the standard XSB notation:
+//!
+//! ```text
+//! #  wall           @  player         $  box
+//! .  goal           +  player on goal *  box on goal
+//!    floor (also `-` and `_`)
+//! ```
+
+use crate::{Board, Obj, Tile, Vec2D};
+use std::fmt;
+
+#[must_use]
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    Empty,
+    UnknownGlyph {
+        line: usize,
+        column: usize,
+        glyph: char,
+    },
+    MissingPlayer,
+    MultiplePlayers {
+        first: Vec2D,
+        second: Vec2D,
+    },
+    BoxGoalMismatch {
+        boxes: usize,
+        goals: usize,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "level is empty"),
+            ParseError::UnknownGlyph {
+                line,
+                column,
+                glyph,
+            } => write!(f, "{}:{}: unknown glyph {:?}", line, column, glyph),
+            ParseError::MissingPlayer => write!(f, "level has no player"),
+            ParseError::MultiplePlayers { first, second } => write!(
+                f,
+                "level has more than one player (at {:?} and {:?})",
+                first, second
+            ),
+            ParseError::BoxGoalMismatch { boxes, goals } => {
+                write!(f, "level has {} boxes but {} goals", boxes, goals)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[must_use]
+pub(crate) fn glyph_to_cell(glyph: char) -> Option<(Tile, Obj)> {
+    match glyph {
+        ' ' | '-' | '_' => Some((Tile::None, Obj::None)),
+        '#' => Some((Tile::Wall, Obj::None)),
+        '.' => Some((Tile::Goal, Obj::None)),
+        '@' => Some((Tile::None, Obj::Player)),
+        '+' => Some((Tile::Goal, Obj::Player)),
+        '$' => Some((Tile::None, Obj::Box)),
+        '*' => Some((Tile::Goal, Obj::Box)),
+        _ => None,
+    }
+}
+
+#[must_use]
+fn cell_to_glyph(tile: Tile, obj: Obj) -> char {
+    match (tile, obj) {
+        (Tile::Wall, _) => '#',
+        (Tile::None, Obj::None) => ' ',
+        (Tile::None, Obj::Player) => '@',
+        (Tile::None, Obj::Box) => '$',
+        (Tile::Goal, Obj::None) => '.',
+        (Tile::Goal, Obj::Player) => '+',
+        (Tile::Goal, Obj::Box) => '*',
+    }
+}
+
+/// Writes `board` as XSB text, one line per row with trailing floor
+/// removed, so that [`parse_board`] reads back the same board. Floor is
+/// written as `-` where removing it would lose a row or column, as on
+/// levels without a surrounding wall.
+#[must_use]
+pub fn to_xsb(board: &Board) -> String {
+    let mut rows: Vec<String> = (0..board.height)
+        .map(|y| {
+            let row: String = (0..board.width)
+                .map(|x| {
+                    let i = board.to_index((x, y));
+                    cell_to_glyph(board.tiles[i], board.objects[i])
+                })
+                .collect();
+            row.trim_end().to_string()
+        })
+        .collect();
+
+    if !rows.iter().any(|row| row.chars().count() == board.width) {
+        if let Some(row) = rows.first_mut() {
+            while row.chars().count() < board.width {
+                row.push('-');
+            }
+        }
+    }
+
+    let mut out = String::new();
+    for row in rows {
+        out.push_str(if row.is_empty() { "-" } else { &row });
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Reads the cells of a single XSB level without checking that it is
+/// playable, so that levels can be inspected for problems. Leading and
+/// trailing blank lines are ignored.
+pub fn parse_layout(text: &str) -> Result<Board, ParseError> {
+    let lines: Vec<&str> = text.lines().map(|l| l.trim_end()).collect();
+    let first = lines.iter().position(|l| !l.is_empty());
+    let last = lines.iter().rposition(|l| !l.is_empty());
+
+    let (first, last) = match (first, last) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Err(ParseError::Empty),
+    };
+
+    let rows = &lines[first..=last];
+    let width = rows.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let height = rows.len();
+
+    // Ragged rows are padded with floor up to the width of the longest row.
+    let mut board = Board::new(width, height);
+
+    for (y, row) in rows.iter().enumerate() {
+        for (x, glyph) in row.chars().enumerate() {
+            let (tile, obj) = glyph_to_cell(glyph).ok_or(ParseError::UnknownGlyph {
+                line: first + y + 1,
+                column: x + 1,
+                glyph,
+            })?;
+
+            let i = board.to_index((x, y));
+            board.tiles[i] = tile;
+            board.objects[i] = obj;
+        }
+    }
+
+    Ok(board)
+}
+
+/// Parses a single XSB level, which must have exactly one player and as
+/// many boxes as goals.
+pub fn parse_board(text: &str) -> Result<Board, ParseError> {
+    let board = parse_layout(text)?;
+
+    let mut players = (0..board.objects.len())
+        .filter(|&i| board.objects[i] == Obj::Player)
+        .map(|i| board.to_vec2d(i));
+
+    match (players.next(), players.next()) {
+        (None, _) => return Err(ParseError::MissingPlayer),
+        (Some(first), Some(second)) => return Err(ParseError::MultiplePlayers { first, second }),
+        (Some(_), None) => {}
+    }
+
+    let (boxes, goals) = (board.count_boxes(), board.count_goals());
+    if boxes != goals {
+        return Err(ParseError::BoxGoalMismatch { boxes, goals });
+    }
+
+    Ok(board)
+}
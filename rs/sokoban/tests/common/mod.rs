@@ -0,0 +1,9 @@
+//! Helpers shared by the tests.
+
+use sokoban::xsb::parse_board;
+use sokoban::Game;
+
+/// A new game of the level in `text`, which must be valid.
+pub fn game(text: &str) -> Game {
+    Game::new(parse_board(text).unwrap())
+}
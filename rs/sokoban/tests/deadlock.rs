@@ -0,0 +1,62 @@
+//! Detecting positions that can no longer be solved.
+
+use sokoban::deadlock::dead_squares;
+use sokoban::xsb::{parse_board, parse_layout};
+use sokoban::{Direction, Game};
+
+fn is_deadlocked(text: &str) -> bool {
+    Game::new(parse_board(text).unwrap()).is_deadlocked()
+}
+
+#[test]
+fn corners_and_walls_without_goals_are_dead() {
+    let board = parse_layout("#######\n#     #\n#  .@ #\n#     #\n#######").unwrap();
+    let dead = dead_squares(&board);
+    let alive: Vec<usize> = (0..dead.len()).filter(|&i| !dead[i]).collect();
+
+    assert_eq!(alive, vec![16, 17, 18]);
+}
+
+#[test]
+fn boxes_in_a_square_are_frozen() {
+    let level = "\
+########
+#@     #
+#  $$  #
+#  $$  #
+#  ..  #
+#  ..  #
+########";
+    assert!(is_deadlocked(level));
+
+    let on_goals = "\
+########
+#@     #
+#  **  #
+#  **$ #
+#    . #
+########";
+    assert!(!is_deadlocked(on_goals));
+}
+
+#[test]
+fn box_on_a_goal_can_freeze_another() {
+    assert!(is_deadlocked("#######\n#@    #\n#     #\n# *$ .#\n#######"));
+
+    // Dead squares either side keep the top box in place.
+    assert!(is_deadlocked("#####\n# * #\n##$##\n#  .#\n# @ #\n#####"));
+}
+
+#[test]
+fn pushing_into_a_deadlock_is_noticed_and_undone() {
+    let mut game = Game::new(parse_board("#####\n#   #\n# $@#\n#.  #\n#####").unwrap());
+    assert!(!game.is_deadlocked());
+
+    assert!(game.move_player(Direction::Down));
+    assert!(game.move_player(Direction::Left));
+    assert!(game.move_player(Direction::Up));
+    assert!(game.is_deadlocked());
+
+    assert!(game.undo());
+    assert!(!game.is_deadlocked());
+}
@@ -0,0 +1,100 @@
+//! Building levels in the editor.
+
+use sokoban::editor::Editor;
+use sokoban::validate::{validate, Problem};
+use sokoban::xsb::to_xsb;
+use sokoban::Direction::{Down, Left, Right, Up};
+
+#[test]
+fn glyphs_are_placed_under_the_cursor() {
+    let mut editor = Editor::empty(5, 3);
+    editor.move_cursor(Down);
+    editor.move_cursor(Right);
+    assert!(editor.place('@'));
+    editor.move_cursor(Right);
+    assert!(editor.place('$'));
+    editor.move_cursor(Right);
+    assert!(editor.place('.'));
+    assert!(!editor.place('x'));
+
+    assert_eq!(to_xsb(editor.board()), "#####\n#@$.#\n#####\n");
+    assert_eq!(validate(editor.board()), vec![]);
+}
+
+#[test]
+fn placing_the_player_moves_it() {
+    let mut editor = Editor::empty(4, 3);
+    editor.move_cursor(Down);
+    editor.move_cursor(Right);
+    assert!(editor.place('+'));
+    editor.move_cursor(Right);
+    assert!(editor.place('@'));
+
+    assert_eq!(to_xsb(editor.board()), "####\n#.@#\n####\n");
+}
+
+#[test]
+fn cursor_stays_on_the_board() {
+    let mut editor = Editor::empty(3, 3);
+    editor.move_cursor(Up);
+    editor.move_cursor(Left);
+    assert_eq!(editor.cursor(), (0, 0));
+
+    for _ in 0..5 {
+        editor.move_cursor(Right);
+        editor.move_cursor(Down);
+    }
+    assert_eq!(editor.cursor(), (2, 2));
+
+    editor.resize(2, 1);
+    assert_eq!(editor.cursor(), (1, 0));
+}
+
+#[test]
+fn resizing_keeps_the_top_left_corner() {
+    let mut editor = Editor::empty(3, 3);
+    editor.resize(4, 2);
+    assert_eq!(to_xsb(editor.board()), "###-\n# #\n");
+
+    editor.resize(0, 0);
+    assert_eq!((editor.board().width, editor.board().height), (1, 1));
+}
+
+#[test]
+fn empty_boards_have_at_least_one_cell() {
+    let editor = Editor::empty(0, 0);
+    assert_eq!((editor.board().width, editor.board().height), (1, 1));
+    assert_eq!(to_xsb(editor.board()), "#\n");
+}
+
+#[test]
+fn unplayable_levels_are_reported() {
+    let mut editor = Editor::empty(7, 3);
+    assert_eq!(
+        validate(editor.board()),
+        vec![Problem::MissingPlayer, Problem::AlreadySolved]
+    );
+
+    editor.move_cursor(Down);
+    editor.move_cursor(Right);
+    assert!(editor.place('@'));
+    editor.move_cursor(Right);
+    assert!(editor.place('$'));
+    editor.move_cursor(Right);
+    assert!(editor.place('#'));
+    editor.move_cursor(Right);
+    assert!(editor.place('*'));
+    editor.move_cursor(Right);
+    assert!(editor.place('$'));
+
+    assert_eq!(
+        validate(editor.board()),
+        vec![
+            Problem::BoxGoalMismatch { boxes: 3, goals: 1 },
+            Problem::DeadBox { at: (2, 1) },
+            Problem::UnreachableBox { at: (4, 1) },
+            Problem::UnreachableBox { at: (5, 1) },
+            Problem::DeadBox { at: (5, 1) },
+        ]
+    );
+}
@@ -0,0 +1,53 @@
+//! Generating new levels.
+
+use sokoban::generate::{generate, GenerateError, Params, Rng};
+use sokoban::{replay, xsb};
+
+const PARAMS: Params = Params {
+    width: 10,
+    height: 8,
+    boxes: 4,
+};
+
+#[test]
+fn generated_levels_are_solvable() {
+    for seed in 0..10 {
+        let level = generate(&PARAMS, &mut Rng::new(seed)).unwrap();
+        let board = &level.board;
+
+        assert_eq!((board.width, board.height), (10, 8));
+        assert_eq!((board.count_boxes(), board.count_goals()), (4, 4));
+        assert!(replay::replay(board, &level.solution.lurd).solved);
+    }
+}
+
+#[test]
+fn seeds_reproduce_levels() {
+    let xsb = |seed| xsb::to_xsb(&generate(&PARAMS, &mut Rng::from_text(seed)).unwrap().board);
+
+    assert_eq!(xsb("2026-10-16"), xsb("2026-10-16"));
+    assert_ne!(xsb("2026-10-16"), xsb("2026-10-17"));
+    assert_eq!(
+        xsb("42"),
+        xsb::to_xsb(&generate(&PARAMS, &mut Rng::new(42)).unwrap().board)
+    );
+}
+
+#[test]
+fn impossible_requests_are_refused() {
+    let mut rng = Rng::new(0);
+    let params = |width, height, boxes| Params {
+        width,
+        height,
+        boxes,
+    };
+
+    assert_eq!(
+        generate(&params(4, 4, 2), &mut rng).err(),
+        Some(GenerateError::TooSmall)
+    );
+    assert_eq!(
+        generate(&params(8, 8, 0), &mut rng).err(),
+        Some(GenerateError::NoBoxes)
+    );
+}
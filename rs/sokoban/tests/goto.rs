@@ -0,0 +1,37 @@
+//! Walking the player to a cell in one command.
+
+mod common;
+
+use common::game;
+
+#[test]
+fn boxes_and_walls_bound_the_reachable_cells() {
+    let g = game("#######\n#@ #  #\n#  $ .#\n#######");
+    let reachable = g.reachable();
+    let reached: Vec<usize> = (0..reachable.len()).filter(|&i| reachable[i]).collect();
+
+    assert_eq!(reached, vec![8, 9, 15, 16]);
+}
+
+#[test]
+fn player_walks_the_shortest_path() {
+    let mut g = game("######\n#@#  #\n#   .#\n#  $ #\n######");
+
+    assert!(g.go_to(10));
+    assert_eq!(g.lurd(), "drrur");
+    assert_eq!((g.moves(), g.pushes()), (5, 0));
+
+    // Each step is a move of its own.
+    assert!(g.undo());
+    assert_eq!(g.player_index(), 9);
+}
+
+#[test]
+fn unreachable_cells_are_refused() {
+    let mut g = game("#######\n#@ #  #\n#  $ .#\n#######");
+
+    assert!(!g.go_to(11));
+    assert!(!g.go_to(17));
+    assert!(!g.go_to(g.player_index()));
+    assert_eq!(g.moves(), 0);
+}
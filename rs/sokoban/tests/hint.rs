@@ -0,0 +1,41 @@
+//! Hints from the solver for games in progress.
+
+mod common;
+
+use common::game;
+use sokoban::solver::{Limits, SolveError};
+use sokoban::Direction::{Down, Left, Right, Up};
+use sokoban::Hint;
+
+#[test]
+fn hint_follows_the_current_position() {
+    // The box has to be pushed left, from the right of it.
+    let mut g = game("######\n#.$ @#\n#    #\n######");
+
+    let hint = g.hint(&Limits::default()).unwrap().unwrap();
+    assert_eq!(
+        hint,
+        Hint {
+            direction: Left,
+            push: (8, Left),
+        }
+    );
+
+    assert!(g.move_player(Down));
+    assert_eq!(g.current_hint(), None);
+
+    let hint = g.hint(&Limits::default()).unwrap().unwrap();
+    assert_eq!(hint.direction, Up);
+    assert_eq!(g.hints(), 2);
+}
+
+#[test]
+fn hints_are_counted_only_when_given() {
+    let mut g = game("#####\n#@$.#\n#####");
+    assert!(g.move_player(Right));
+    assert_eq!(g.hint(&Limits::default()), Ok(None));
+
+    let mut g = game("######\n#@ .$#\n######");
+    assert_eq!(g.hint(&Limits::default()), Err(SolveError::Unsolvable));
+    assert_eq!(g.hints(), 0);
+}
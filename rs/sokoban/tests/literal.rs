@@ -0,0 +1,37 @@
+//! Writing boards as Rust layer literals.
+
+use sokoban::literal::to_literal;
+use sokoban::xsb::parse_board;
+
+#[test]
+fn layers_are_written_row_by_row() {
+    let board = parse_board("####\n#+$#\n####").unwrap();
+
+    assert_eq!(
+        to_literal(&board),
+        "\
+const LEVEL_WIDTH: usize = 4;
+const LEVEL_HEIGHT: usize = 3;
+
+#[rustfmt::skip]
+static TILE_LAYER: [Tile; LEVEL_WIDTH * LEVEL_HEIGHT] = {
+    #[allow(non_snake_case)]
+    let (o, H, X) = (Tile::None, Tile::Wall, Tile::Goal);
+
+    [H,H,H,H,
+     H,X,o,H,
+     H,H,H,H]
+};
+
+#[rustfmt::skip]
+static OBJECT_LAYER: [Obj; LEVEL_WIDTH * LEVEL_HEIGHT] = {
+    #[allow(non_snake_case)]
+    let (o, P, B) = (Obj::None, Obj::Player, Obj::Box);
+
+    [o,o,o,o,
+     o,P,B,o,
+     o,o,o,o]
+};
+"
+    );
+}
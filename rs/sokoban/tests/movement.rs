@@ -0,0 +1,73 @@
+//! Movement at the edges of boards without a surrounding wall.
+
+mod common;
+
+use common::game;
+use sokoban::Direction::{Down, Left, Right, Up};
+use sokoban::Obj;
+
+#[test]
+fn player_stops_at_every_edge() {
+    let mut g = game("@ $.\n----\n----");
+
+    assert!(!g.move_player(Left));
+    assert!(!g.move_player(Up));
+    assert_eq!(g.player_index(), 0);
+
+    assert!(g.move_player(Down));
+    assert!(g.move_player(Down));
+    assert!(!g.move_player(Down));
+    assert_eq!(g.moves(), 2);
+}
+
+#[test]
+fn player_stops_in_every_corner() {
+    let corners = [
+        ("@ \n $\n .", [Left, Up]),
+        (" @\n$ \n. ", [Right, Up]),
+        (". \n$ \n@ ", [Left, Down]),
+        (" .\n $\n @", [Right, Down]),
+    ];
+
+    for (text, directions) in corners.iter() {
+        let mut g = game(text);
+        let start = g.player_index();
+
+        for direction in directions.iter() {
+            assert!(
+                !g.move_player(*direction),
+                "{:?} from {:?}",
+                direction,
+                text
+            );
+        }
+        assert_eq!(g.player_index(), start);
+        assert_eq!(g.moves(), 0);
+    }
+}
+
+#[test]
+fn box_cannot_be_pushed_off_the_board() {
+    let mut g = game(". @$");
+
+    assert!(!g.move_player(Right));
+    assert!(g.board().objects[3] == Obj::Box);
+    assert_eq!(g.pushes(), 0);
+    assert_eq!(g.lurd(), "");
+}
+
+#[test]
+fn box_pushed_along_the_edge_can_be_undone() {
+    let mut g = game("@$ .\n----");
+
+    assert!(g.move_player(Right));
+    assert!(g.move_player(Right));
+    assert!(!g.move_player(Up));
+    assert_eq!(g.lurd(), "RR");
+
+    assert!(g.undo());
+    assert!(g.undo());
+    assert_eq!(g.player_index(), 0);
+    assert!(g.board().objects[1] == Obj::Box);
+    assert_eq!(g.goals_left(), 1);
+}
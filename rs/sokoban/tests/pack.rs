@@ -0,0 +1,84 @@
+//! Reading level packs.
+
+use sokoban::pack::{entries, LevelPack, PackError};
+use sokoban::xsb::ParseError;
+
+const LEVEL: &str = "#####\n#@$.#\n#####";
+
+fn titles(text: &str) -> Vec<String> {
+    let pack = LevelPack::parse(text).unwrap();
+    pack.levels.into_iter().map(|level| level.title).collect()
+}
+
+#[test]
+fn titles_name_the_level_they_belong_to() {
+    let before = format!("Title: A\n{0}\n\nTitle: B\n{0}\n", LEVEL);
+    assert_eq!(titles(&before), ["A", "B"]);
+
+    let below = format!("{0}\nTitle: A\n\n{0}\nTitle: B\n", LEVEL);
+    assert_eq!(titles(&below), ["A", "B"]);
+
+    let captions = format!("; 1\n{0}\n\nSecond\n\n{0}\n\n{0}\n", LEVEL);
+    assert_eq!(titles(&captions), ["1", "Second", "Level 3"]);
+}
+
+#[test]
+fn metadata_lines_are_not_titles() {
+    let text = format!(
+        "{0}\nTitle: A\nAuthor: Someone\n\n{0}\nComment: easy\n\n{0}\n",
+        LEVEL
+    );
+    assert_eq!(titles(&text), ["A", "Level 2", "Level 3"]);
+}
+
+#[test]
+fn errors_name_the_level_and_line() {
+    assert_eq!(
+        LevelPack::parse("Nothing here\n").err(),
+        Some(PackError::NoLevels)
+    );
+    assert_eq!(
+        LevelPack::parse(&format!("{0}\n\n#####\n#@$x#\n#####\n", LEVEL)).err(),
+        Some(PackError::Level {
+            number: 2,
+            error: ParseError::UnknownGlyph {
+                line: 6,
+                column: 4,
+                glyph: 'x',
+            },
+        })
+    );
+}
+
+#[test]
+fn entries_are_split_without_reading_boards() {
+    let text = format!("{0}\n\nTitle: Bad\n#####\n#@x.#\n#####\n", LEVEL);
+    let entries = entries(&text);
+
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].title, "Bad");
+    assert_eq!(entries[1].rows, "#####\n#@x.#\n#####");
+    assert_eq!(entries[1].first_line, 6);
+}
+
+#[test]
+fn levels_need_not_be_walled() {
+    let pack = LevelPack::parse("Title: Open\n@ $.\n").unwrap();
+    assert_eq!(pack.levels.len(), 1);
+    assert_eq!(pack.levels[0].title, "Open");
+    assert_eq!(pack.levels[0].board.width, 4);
+
+    let pack = LevelPack::parse("####\n @$.#\n####\n").unwrap();
+    assert_eq!(pack.levels[0].board.height, 3);
+}
+
+#[test]
+fn floor_rows_belong_to_the_level() {
+    let below = LevelPack::parse("@ $.\n----\n-\n").unwrap();
+    let board = &below.levels[0].board;
+    assert_eq!((board.width, board.height), (4, 3));
+
+    let above = LevelPack::parse("Title: A\n-\n$@.\n").unwrap();
+    assert_eq!(above.levels[0].title, "A");
+    assert_eq!(above.levels[0].board.height, 2);
+}
@@ -0,0 +1,60 @@
+//! Per-level best scores.
+
+use sokoban::records::{level_key, Records};
+use sokoban::xsb::parse_board;
+use sokoban::{Direction, Game};
+
+const LEVEL: &str = "#######\n#@ $ .#\n#######";
+
+fn solve(lurd: &str) -> Game {
+    let mut game = Game::new(parse_board(LEVEL).unwrap());
+    for c in lurd.chars() {
+        let (direction, _) = Direction::from_lurd(c).unwrap();
+        assert!(game.move_player(direction));
+    }
+    game
+}
+
+#[test]
+fn levels_are_keyed_by_layout() {
+    let board = parse_board(LEVEL).unwrap();
+    let moved = solve("r");
+
+    assert_eq!(level_key(&board), level_key(&parse_board(LEVEL).unwrap()));
+    assert_ne!(level_key(&board), level_key(moved.board()));
+}
+
+#[test]
+fn best_results_are_kept_across_attempts() {
+    let board = parse_board(LEVEL).unwrap();
+    let mut records = Records::default();
+
+    assert!(!records.add(&board, &solve("r")).any());
+    assert!(records.add(&board, &solve("rRlrR")).moves);
+    assert!(!records.add(&board, &solve("rlrRR")).moves);
+    assert!(records.add(&board, &solve("rRR")).moves);
+
+    let record = records.get(&board).unwrap();
+    assert_eq!(record.attempts, 4);
+    assert_eq!(record.best_moves, Some(3));
+    assert_eq!(record.best_pushes, Some(2));
+    assert_eq!(record.best_lurd.as_deref(), Some("rRR"));
+
+    let text = records.to_text();
+    assert_eq!(Records::parse(&text).to_text(), text);
+}
+
+#[test]
+fn damaged_lines_are_skipped() {
+    let board = parse_board(LEVEL).unwrap();
+    let text = format!(
+        "garbage\n{:016x} 3 5 2 1500 rRlrR\n0123 x - - - -\n\u{0}\u{1}\n0456 1 2\n",
+        level_key(&board)
+    );
+
+    let records = Records::parse(&text);
+    let record = records.get(&board).unwrap();
+    assert_eq!(record.attempts, 3);
+    assert_eq!(record.best_moves, Some(5));
+    assert_eq!(records.to_text().lines().count(), 1);
+}
@@ -0,0 +1,57 @@
+//! Redrawing only what changed between frames.
+
+use sokoban::render::{diff, Cell, Frame};
+
+fn frame(lines: &[&str]) -> Frame {
+    let mut frame = Frame::new();
+    for line in lines {
+        frame.text(line);
+    }
+    frame
+}
+
+#[test]
+fn unchanged_frames_draw_nothing() {
+    assert_eq!(diff(&frame(&["abc", "de"]), &frame(&["abc", "de"])), "");
+}
+
+#[test]
+fn changed_cells_are_drawn_in_one_run() {
+    assert_eq!(diff(&frame(&["abc"]), &frame(&["abd"])), "\x1b[1;3Hd");
+    assert_eq!(diff(&frame(&["abc"]), &frame(&["xyc"])), "\x1b[1;1Hxy");
+    assert_eq!(
+        diff(&frame(&["abcd"]), &frame(&["xbcy"])),
+        "\x1b[1;1Hx\x1b[1;4Hy"
+    );
+    assert_eq!(diff(&frame(&["", "a"]), &frame(&["", "b"])), "\x1b[2;1Hb");
+}
+
+#[test]
+fn shorter_rows_are_cleared() {
+    assert_eq!(diff(&frame(&["abc"]), &frame(&["ab"])), "\x1b[1;3H\x1b[K");
+    assert_eq!(diff(&frame(&["a", "b"]), &frame(&["a"])), "\x1b[2;1H\x1b[K");
+}
+
+#[test]
+fn cells_after_a_width_change_are_redrawn() {
+    let wide = Cell {
+        text: "[]".to_string(),
+        width: 2,
+        highlight: false,
+    };
+    let mut previous = Frame::new();
+    previous.row(vec![wide, Cell::new('c')]);
+
+    assert_eq!(
+        diff(&previous, &frame(&["ac"])),
+        "\x1b[1;1Hac\x1b[1;3H\x1b[K"
+    );
+}
+
+#[test]
+fn highlighted_cells_are_reversed() {
+    let mut next = Frame::new();
+    next.row(vec![Cell::new('a'), Cell::new('b').highlighted()]);
+
+    assert_eq!(diff(&frame(&["ac"]), &next), "\x1b[1;2H\x1b[7mb\x1b[27m");
+}
@@ -0,0 +1,70 @@
+//! Checking solutions by playing them back.
+
+use sokoban::replay::{replay, ReplayError};
+use sokoban::xsb::{parse_board, to_xsb};
+
+const LEVEL: &str = "#######\n#@ $ .#\n#######";
+
+fn error(lurd: &str) -> Option<ReplayError> {
+    replay(&parse_board(LEVEL).unwrap(), lurd).error
+}
+
+#[test]
+fn solutions_are_accepted() {
+    let report = replay(&parse_board(LEVEL).unwrap(), "r RR");
+
+    assert!(report.valid() && report.solved);
+    assert_eq!((report.moves, report.pushes), (3, 2));
+    assert_eq!(to_xsb(&report.board), "#######\n#   @*#\n#######\n");
+}
+
+#[test]
+fn errors_give_the_offending_move() {
+    assert_eq!(
+        error("rx"),
+        Some(ReplayError::UnknownChar {
+            position: 2,
+            c: 'x'
+        })
+    );
+    assert_eq!(
+        error("l"),
+        Some(ReplayError::IllegalMove {
+            position: 1,
+            c: 'l'
+        })
+    );
+    assert_eq!(
+        error("rr"),
+        Some(ReplayError::PushMismatch {
+            position: 2,
+            c: 'r'
+        })
+    );
+    assert_eq!(
+        error("R"),
+        Some(ReplayError::PushMismatch {
+            position: 1,
+            c: 'R'
+        })
+    );
+    assert_eq!(
+        error("rRRl"),
+        Some(ReplayError::MovesAfterSolved { position: 4 })
+    );
+}
+
+#[test]
+fn replay_stops_at_the_first_error() {
+    let report = replay(&parse_board(LEVEL).unwrap(), "rRuR");
+
+    assert!(!report.valid() && !report.solved);
+    assert_eq!((report.moves, report.pushes), (2, 1));
+    assert_eq!(to_xsb(&report.board), "#######\n#  @$.#\n#######\n");
+
+    let report = replay(&parse_board(LEVEL).unwrap(), "rRr");
+
+    assert!(!report.valid());
+    assert_eq!((report.moves, report.pushes), (2, 1));
+    assert_eq!(to_xsb(&report.board), "#######\n#  @$.#\n#######\n");
+}
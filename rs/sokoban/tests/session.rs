@@ -0,0 +1,66 @@
+//! Saving and restoring games in progress.
+
+use sokoban::session::Session;
+use sokoban::xsb::{parse_board, to_xsb};
+use sokoban::{Direction, Game};
+use std::time::Duration;
+
+#[test]
+fn saved_game_resumes_where_it_left_off() {
+    let board = parse_board("######\n#@ $.#\n# $. #\n######").unwrap();
+    let mut game = Game::new(board.clone());
+    assert!(game.move_player(Direction::Right));
+    assert!(game.move_player(Direction::Right));
+    game.set_hints(3);
+
+    let session = Session::new(Some("levels.txt".to_string()), 2, board, &game);
+    let restored = Session::parse(&session.to_text()).unwrap();
+    assert_eq!(restored.pack.as_deref(), Some("levels.txt"));
+    assert_eq!(restored.level, 2);
+
+    let resumed = restored.to_game().unwrap();
+    assert_eq!(to_xsb(resumed.board()), to_xsb(game.board()));
+    assert_eq!(resumed.lurd(), "rR");
+    assert_eq!((resumed.moves(), resumed.pushes()), (2, 1));
+    assert_eq!(resumed.goals_left(), 1);
+    assert_eq!(resumed.hints(), 3);
+}
+
+#[test]
+fn games_on_open_levels_resume() {
+    let board = parse_board("@ $.\n----\n----").unwrap();
+    let mut game = Game::new(board.clone());
+    assert!(game.move_player(Direction::Down));
+
+    let session = Session::parse(&Session::new(None, 0, board.clone(), &game).to_text()).unwrap();
+    assert!(session.board == board);
+
+    let resumed = session.to_game().unwrap();
+    assert_eq!(resumed.lurd(), "d");
+    assert_eq!(resumed.player_index(), 4);
+}
+
+#[test]
+fn elapsed_time_carries_over() {
+    let text = "level = 0\nelapsed = 95\nlurd = r\n\n#####\n#@$.#\n#####\n";
+    let game = Session::parse(text).unwrap().to_game().unwrap();
+
+    assert!(game.elapsed() >= Duration::from_secs(95));
+    assert!(game.elapsed() < Duration::from_secs(96));
+    assert_eq!(game.hints(), 0);
+}
+
+#[test]
+fn malformed_sessions_are_rejected() {
+    let level = "\n\n#####\n#@$.#\n#####\n";
+
+    assert!(Session::parse("").is_none());
+    assert!(Session::parse(&format!("level = x\nelapsed = 0\nlurd ={}", level)).is_none());
+    assert!(Session::parse(&format!("level = 0\nlurd = r{}", level)).is_none());
+    assert!(
+        Session::parse(&format!("level = 0\nelapsed = 0\nlurd = l{}", level))
+            .unwrap()
+            .to_game()
+            .is_none()
+    );
+}
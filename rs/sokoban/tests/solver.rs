@@ -0,0 +1,62 @@
+//! Solving levels.
+
+use sokoban::replay::replay;
+use sokoban::solver::{solve, Limits, SolveError};
+use sokoban::xsb::{parse_board, parse_layout};
+use sokoban::{Direction, Game};
+
+const LEVEL: &str = "\
+#######
+#.   .#
+# $@$ #
+#     #
+#######";
+
+#[test]
+fn solutions_are_valid_and_short() {
+    let board = parse_board(LEVEL).unwrap();
+    let solution = solve(&board, &Limits::default()).unwrap();
+
+    assert_eq!(solution.pushes, 4);
+    let report = replay(&board, &solution.lurd);
+    assert!(report.valid() && report.solved);
+    assert_eq!(report.pushes, 4);
+}
+
+#[test]
+fn games_in_progress_are_solved_from_where_they_stand() {
+    let mut game = Game::new(parse_board(LEVEL).unwrap());
+    assert!(game.move_player(Direction::Right));
+
+    let solution = solve(game.board(), &Limits::default()).unwrap();
+    assert_eq!(solution.pushes, 3);
+    assert!(replay(game.board(), &solution.lurd).solved);
+
+    let mut won = Game::new(parse_board("#####\n#@$.#\n#####").unwrap());
+    assert!(won.move_player(Direction::Right));
+    assert_eq!(solve(won.board(), &Limits::default()).unwrap().lurd, "");
+}
+
+#[test]
+fn failures_are_reported() {
+    let dead = parse_board("######\n#@ .$#\n######").unwrap();
+    assert_eq!(
+        solve(&dead, &Limits::default()).err(),
+        Some(SolveError::Unsolvable)
+    );
+
+    let empty = parse_layout("#####\n# $.#\n#####").unwrap();
+    assert_eq!(
+        solve(&empty, &Limits::default()).err(),
+        Some(SolveError::MissingPlayer)
+    );
+
+    let limits = Limits {
+        max_nodes: 0,
+        ..Limits::default()
+    };
+    assert_eq!(
+        solve(&parse_board(LEVEL).unwrap(), &limits).err(),
+        Some(SolveError::NodeLimit)
+    );
+}
@@ -0,0 +1,39 @@
+//! Undoing and redoing moves.
+
+mod common;
+
+use common::game;
+use sokoban::Direction::{Left, Right};
+use sokoban::{GameStatus, Obj};
+
+#[test]
+fn undo_and_redo_restore_pushes_onto_goals() {
+    let mut g = game("#######\n#@$ ..#\n#  $  #\n#######");
+
+    assert!(g.move_player(Right));
+    assert!(g.move_player(Right));
+    assert_eq!((g.moves(), g.pushes(), g.goals_left()), (2, 2, 1));
+    assert!(g.board().objects[11] == Obj::Box);
+
+    assert!(g.undo());
+    assert_eq!((g.moves(), g.pushes(), g.goals_left()), (1, 1, 2));
+    assert!(g.board().objects[10] == Obj::Box);
+    assert!(g.board().objects[11] == Obj::None);
+
+    assert!(g.redo());
+    assert_eq!((g.moves(), g.pushes(), g.goals_left()), (2, 2, 1));
+    assert!(g.board().objects[11] == Obj::Box);
+    assert_eq!(g.status(), GameStatus::Playing);
+}
+
+#[test]
+fn moves_clear_what_could_be_redone() {
+    let mut g = game("######\n# @$.#\n######");
+
+    assert!(g.move_player(Right));
+    assert!(g.undo());
+    assert!(g.move_player(Left));
+
+    assert!(!g.redo());
+    assert_eq!((g.moves(), g.pushes()), (1, 0));
+}
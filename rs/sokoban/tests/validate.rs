@@ -0,0 +1,78 @@
+//! Checking levels for structural problems.
+
+use sokoban::validate::{is_valid, level_json, validate, validate_text, Problem};
+use sokoban::xsb::parse_layout;
+
+fn problems(text: &str) -> Vec<Problem> {
+    validate(&parse_layout(text).unwrap())
+}
+
+#[test]
+fn playable_levels_have_no_problems() {
+    assert_eq!(problems("#####\n#@$.#\n#####"), vec![]);
+}
+
+#[test]
+fn players_and_counts_are_checked() {
+    assert_eq!(
+        problems("#####\n# $.#\n#####"),
+        vec![Problem::MissingPlayer]
+    );
+    assert_eq!(
+        problems("######\n#@@$.#\n######"),
+        vec![Problem::MultiplePlayers { count: 2 }]
+    );
+    assert_eq!(
+        problems("######\n#@$$.#\n######"),
+        vec![Problem::BoxGoalMismatch { boxes: 2, goals: 1 }]
+    );
+    assert_eq!(problems("####\n#@*#\n####"), vec![Problem::AlreadySolved]);
+}
+
+#[test]
+fn cells_the_player_cant_use_are_reported() {
+    assert_eq!(
+        problems("#######\n#@$.#*#\n#######"),
+        vec![Problem::UnreachableBox { at: (5, 1) }]
+    );
+    assert_eq!(
+        problems("#######\n#@ $#.#\n#######"),
+        vec![
+            Problem::DeadBox { at: (3, 1) },
+            Problem::UnreachableGoal { at: (5, 1) }
+        ]
+    );
+}
+
+#[test]
+fn open_borders_are_only_warnings() {
+    let found = problems("####\n @$.#\n####");
+
+    assert_eq!(found, vec![Problem::OpenBorder { at: (0, 1) }]);
+    assert!(is_valid(&found));
+}
+
+#[test]
+fn reports_are_json() {
+    let found = problems("####\n#@$#\n#. #\n####");
+
+    assert_eq!(
+        level_json(2, "A \"test\"", &found),
+        "{\"level\": 2, \"title\": \"A \\\"test\\\"\", \"valid\": false, \"problems\": [\
+         {\"code\": \"dead_box\", \"severity\": \"error\", \"x\": 2, \"y\": 1, \
+         \"message\": \"box at (2, 1) can never be pushed onto a goal\"}]}"
+    );
+}
+
+#[test]
+fn unreadable_levels_are_problems_too() {
+    assert_eq!(
+        validate_text("\n#####\n#@$%#\n#####"),
+        vec![Problem::UnknownGlyph {
+            at: (3, 1),
+            glyph: '%',
+        }]
+    );
+    assert_eq!(validate_text("\n\n"), vec![Problem::Empty]);
+    assert_eq!(validate_text("#####\n#@$.#\n#####"), vec![]);
+}
@@ -0,0 +1,95 @@
+//! Reading and writing XSB levels.
+
+use sokoban::theme::Theme;
+use sokoban::xsb::{parse_board, to_xsb, ParseError};
+use sokoban::{Direction, Game, Obj, Tile};
+
+const LEVEL: &str = "\
+  #####
+###   #
+#.@$  #
+### $.#
+#.##$ #
+# # . ##
+#$ *$$.#
+#   .  #
+########
+";
+
+#[test]
+fn every_glyph_round_trips() {
+    let open = ["@ $.\n-\n-\n", " @$.-\n", "-\n$@.\n"];
+    for text in [LEVEL, "#####\n#+$*#\n#####\n", "@$.\n"]
+        .iter()
+        .chain(&open)
+    {
+        let board = parse_board(text).unwrap();
+        assert_eq!(to_xsb(&board), *text);
+        assert_eq!(to_xsb(&parse_board(&to_xsb(&board)).unwrap()), *text);
+    }
+
+    let board = parse_board("@ $.\n----\n----").unwrap();
+    let read_back = parse_board(&to_xsb(&board)).unwrap();
+    assert_eq!((read_back.width, read_back.height), (4, 3));
+}
+
+#[test]
+fn goals_keep_their_objects_in_separate_layers() {
+    let board = parse_board("#+*.$$#").unwrap();
+
+    assert!(board.tiles[1] == Tile::Goal && board.objects[1] == Obj::Player);
+    assert!(board.tiles[2] == Tile::Goal && board.objects[2] == Obj::Box);
+    assert!(board.tiles[3] == Tile::Goal && board.objects[3] == Obj::None);
+    assert!(board.tiles[4] == Tile::None && board.objects[4] == Obj::Box);
+}
+
+#[test]
+fn player_leaving_a_goal_uncovers_it() {
+    let mut game = Game::new(parse_board("#######\n#+ $.$#\n#######").unwrap());
+    assert!(game.move_player(Direction::Right));
+    assert_eq!(to_xsb(game.board()), "#######\n#.@$.$#\n#######\n");
+
+    assert!(game.undo());
+    assert_eq!(to_xsb(game.board()), "#######\n#+ $.$#\n#######\n");
+}
+
+#[test]
+fn every_theme_draws_the_player_on_a_goal_differently() {
+    for name in Theme::names() {
+        let theme = Theme::by_name(name).unwrap();
+        assert_ne!(
+            theme.cell(Obj::Player, Tile::Goal).text,
+            theme.cell(Obj::Player, Tile::None).text,
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn parse_errors_locate_the_problem() {
+    assert_eq!(parse_board("\n  \n").err(), Some(ParseError::Empty));
+    assert_eq!(
+        parse_board("\n#####\n#@$.#\n##x##").err(),
+        Some(ParseError::UnknownGlyph {
+            line: 4,
+            column: 3,
+            glyph: 'x',
+        })
+    );
+    assert_eq!(
+        parse_board("#####\n# $.#\n#####").err(),
+        Some(ParseError::MissingPlayer)
+    );
+    assert_eq!(
+        parse_board("#####\n#@$.#\n#$.@#\n#####").err(),
+        Some(ParseError::MultiplePlayers {
+            first: (1, 1),
+            second: (3, 2),
+        })
+    );
+    assert_eq!(
+        parse_board("######\n#@$$.#\n######").err(),
+        Some(ParseError::BoxGoalMismatch { boxes: 2, goals: 1 })
+    );
+}